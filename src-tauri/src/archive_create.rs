@@ -0,0 +1,462 @@
+//! Creation of new archives through the bundled 7-Zip executable.
+//! 通过捆绑的 7-Zip 可执行文件创建新的压缩包。
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use serde::{Serialize, Deserialize};
+
+use super::logging::{log_info, log_error};
+use super::archive_utils::{check_7z_output, create_temp_dir, password_switch};
+use super::progress::{ProgressReporter, run_7z_with_progress};
+use super::jobs::JobHandle;
+use super::error::SoarZipError;
+
+/// Archive formats that SoarZip can write.
+/// SoarZip 可以写入的压缩包格式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArchiveFormat {
+    #[serde(rename = "zip")]
+    Zip,
+    #[serde(rename = "7z")]
+    SevenZip,
+    #[serde(rename = "tar")]
+    Tar,
+    #[serde(rename = "tar.gz")]
+    TarGz,
+    #[serde(rename = "tar.xz")]
+    TarXz,
+}
+
+impl ArchiveFormat {
+    /// Guesses the format from an archive file name (e.g., `backup.tar.gz` -> `TarGz`).
+    ///
+    /// 根据压缩包文件名推测格式（例如 `backup.tar.gz` -> `TarGz`）。
+    pub fn from_path(path: &Path) -> Option<ArchiveFormat> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if name.ends_with(".tar.xz") || name.ends_with(".txz") {
+            Some(ArchiveFormat::TarXz)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else if name.ends_with(".7z") {
+            Some(ArchiveFormat::SevenZip)
+        } else {
+            None
+        }
+    }
+
+    /// The 7-Zip `-t` switch for the container written from the source files.
+    /// Compressed tarballs are written as a plain tar first.
+    ///
+    /// 从源文件写入的容器对应的 7-Zip `-t` 开关。
+    /// 压缩的 tar 包会先写为普通 tar。
+    fn container_switch(self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "-tzip",
+            ArchiveFormat::SevenZip => "-t7z",
+            ArchiveFormat::Tar | ArchiveFormat::TarGz | ArchiveFormat::TarXz => "-ttar",
+        }
+    }
+
+    /// The 7-Zip `-t` switch for the outer stream compressor, if the format has one.
+    ///
+    /// 外层流压缩器的 7-Zip `-t` 开关（如果该格式有的话）。
+    fn compressor_switch(self) -> Option<&'static str> {
+        match self {
+            ArchiveFormat::TarGz => Some("-tgzip"),
+            ArchiveFormat::TarXz => Some("-txz"),
+            _ => None,
+        }
+    }
+}
+
+/// Cipher used to encrypt zip entries. 7z archives always use AES-256.
+/// 用于加密 zip 条目的加密算法。7z 压缩包始终使用 AES-256。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EncryptionMethod {
+    /// AES-256, the default / AES-256，默认值
+    #[default]
+    Aes256,
+    /// Legacy ZipCrypto, which is weak but readable by old tools. Only used when requested explicitly.
+    /// 传统 ZipCrypto，强度较弱但旧工具可读。仅在明确请求时使用。
+    ZipCrypto,
+}
+
+/// Password protection for a new archive, sent by the frontend.
+/// 新压缩包的密码保护选项，由前端发送。
+#[derive(Clone, Deserialize)]
+pub struct EncryptionOptions {
+    /// The password to encrypt with. It is never logged.
+    pub password: String,
+    /// Also encrypt the file names (`-mhe=on`); only supported for 7z.
+    #[serde(default)]
+    pub encrypt_file_names: bool,
+    /// The cipher for zip archives.
+    #[serde(default)]
+    pub method: EncryptionMethod,
+}
+
+/// Masks the password, so formatting the options for a log never reveals it.
+/// 屏蔽密码，使为日志格式化选项时永远不会泄露密码。
+impl fmt::Debug for EncryptionOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptionOptions")
+            .field("password", &"***")
+            .field("encrypt_file_names", &self.encrypt_file_names)
+            .field("method", &self.method)
+            .finish()
+    }
+}
+
+impl EncryptionOptions {
+    /// Validates the options for a format and builds the 7-Zip switches that apply them.
+    ///
+    /// 针对某种格式校验选项，并构建应用这些选项的 7-Zip 开关。
+    fn switches(&self, format: ArchiveFormat) -> Result<Vec<String>, SoarZipError> {
+        if self.password.is_empty() {
+            return Err(SoarZipError::InvalidInput("The password for an encrypted archive must not be empty.".to_string()));
+        }
+        let mut switches: Vec<String> = password_switch(Some(&self.password)).into_iter().collect();
+        match format {
+            ArchiveFormat::SevenZip => {
+                if self.method == EncryptionMethod::ZipCrypto {
+                    return Err(SoarZipError::UnsupportedFormat("ZipCrypto is only available for zip archives; 7z archives use AES-256.".to_string()));
+                }
+                if self.encrypt_file_names {
+                    switches.push("-mhe=on".to_string());
+                }
+            }
+            ArchiveFormat::Zip => {
+                if self.encrypt_file_names {
+                    return Err(SoarZipError::UnsupportedFormat("Zip archives cannot encrypt file names; use the 7z format instead.".to_string()));
+                }
+                match self.method {
+                    EncryptionMethod::Aes256 => switches.push("-mem=AES256".to_string()),
+                    EncryptionMethod::ZipCrypto => {
+                        log_info("Weak ZipCrypto encryption was explicitly requested for a zip archive.");
+                        switches.push("-mem=ZipCrypto".to_string());
+                    }
+                }
+            }
+            ArchiveFormat::Tar | ArchiveFormat::TarGz | ArchiveFormat::TarXz => {
+                return Err(SoarZipError::UnsupportedFormat("Tar archives do not support encryption; use the 7z or zip format instead.".to_string()));
+            }
+        }
+        Ok(switches)
+    }
+}
+
+/// Summary of a successfully created archive, returned to the frontend.
+/// 成功创建的压缩包摘要，返回给前端。
+#[derive(Debug, Serialize, Clone)]
+pub struct CreatedArchive {
+    /// The absolute path of the written archive.
+    pub archive_path: String,
+    /// The format the archive was written in.
+    pub format: ArchiveFormat,
+    /// The size of the archive file on disk in bytes.
+    pub archive_size: u64,
+    /// Whether the archive is password protected.
+    pub encrypted: bool,
+    /// Warnings 7-Zip reported while writing, e.g. source files that were locked and left out.
+    pub warnings: Vec<String>,
+}
+
+/// Creates a new archive from files and folders on disk.
+///
+/// When `relative_root` is given, 7-Zip runs from that directory and every source is passed
+/// relative to it, so entries keep their folder structure below the root. Without a root,
+/// each source is stored under its own name.
+///
+/// An encrypted archive is test-opened with its password after it has been written; if that
+/// fails, the archive is removed and an error is returned.
+///
+/// # Arguments
+///
+/// * `seven_zip_path` - The path to the 7-Zip executable.
+/// * `source_paths` - Files and folders to put into the archive.
+/// * `archive_path` - The archive file to create. It must not exist yet.
+/// * `format` - The archive format to write.
+/// * `relative_root` - Optional directory that entry paths are made relative to.
+/// * `encryption` - Optional password protection (7z and zip only).
+/// * `reporter` - Receives progress while 7-Zip runs.
+/// * `job` - The job the 7-Zip processes belong to, for cancellation.
+///
+/// # Returns
+///
+/// * `Ok((PathBuf, Vec<String>))` - The absolute path of the created archive, and the warnings 7-Zip
+///   reported if it finished with warnings (the archive is kept then).
+/// * `Err(SoarZipError)` - The error if validation or 7-Zip fails.
+///
+/// 从磁盘上的文件和文件夹创建新的压缩包。
+///
+/// 如果给出了 `relative_root`，7-Zip 会在该目录中运行，并以相对于该目录的形式传入每个源，
+/// 因此条目会保留根目录以下的目录结构。没有根目录时，每个源以其自身名称保存。
+///
+/// 加密压缩包写入后会使用其密码进行试打开；如果失败，则删除该压缩包并返回错误。
+///
+/// # 参数
+///
+/// * `seven_zip_path` - 7-Zip 可执行文件的路径。
+/// * `source_paths` - 要放入压缩包的文件和文件夹。
+/// * `archive_path` - 要创建的压缩包文件，必须尚不存在。
+/// * `format` - 要写入的压缩包格式。
+/// * `relative_root` - 可选的目录，条目路径将相对于该目录。
+/// * `encryption` - 可选的密码保护（仅限 7z 和 zip）。
+/// * `reporter` - 在 7-Zip 运行期间接收进度。
+/// * `job` - 7-Zip 进程所属的作业，用于取消。
+///
+/// # 返回值
+///
+/// * `Ok((PathBuf, Vec<String>))` - 已创建压缩包的绝对路径，以及 7-Zip 带警告完成时报告的警告（此时会保留压缩包）。
+/// * `Err(SoarZipError)` - 如果校验或 7-Zip 失败，则返回相应错误。
+#[allow(clippy::too_many_arguments)]
+pub fn create_archive_with_7z(
+    seven_zip_path: &Path,
+    source_paths: &[String],
+    archive_path: &Path,
+    format: ArchiveFormat,
+    relative_root: Option<&Path>,
+    encryption: Option<&EncryptionOptions>,
+    reporter: &mut ProgressReporter,
+    job: &JobHandle,
+) -> Result<(PathBuf, Vec<String>), SoarZipError> {
+    if source_paths.is_empty() {
+        return Err(SoarZipError::InvalidInput("No source files were given for the new archive.".to_string()));
+    }
+    let mut container_switches = vec![format.container_switch().to_string()];
+    if let Some(options) = encryption {
+        container_switches.extend(options.switches(format)?);
+    }
+
+    let archive_path = absolute_target_path(archive_path)?;
+    if archive_path.exists() {
+        let error_msg = format!("Target archive already exists: {}", archive_path.display());
+        log_error(&error_msg);
+        return Err(SoarZipError::InvalidInput(error_msg));
+    }
+
+    let (working_dir, entries) = resolve_source_entries(source_paths, relative_root)?;
+
+    let result = match format.compressor_switch() {
+        None => match encryption {
+            Some(options) => {
+                // Leave the last part of the progress bar for the verification pass
+                // 为校验过程保留进度条的最后一部分
+                reporter.begin_phase(0, 80);
+                add_entries(seven_zip_path, &container_switches, &archive_path, &entries, working_dir.as_deref(), reporter, job)
+                    .and_then(|warnings| {
+                        reporter.begin_phase(80, 20);
+                        verify_encrypted_archive(seven_zip_path, &archive_path, &options.password, reporter, job).map(|_| warnings)
+                    })
+            }
+            None => add_entries(seven_zip_path, &container_switches, &archive_path, &entries, working_dir.as_deref(), reporter, job),
+        },
+        Some(compressor_switch) => {
+            // 7-Zip cannot write a compressed tarball in one pass, so pack a tar in a
+            // scratch directory first and then compress that single file.
+            // 7-Zip 无法一次写出压缩的 tar 包，因此先在临时目录中打包 tar，再压缩该单个文件。
+            let temp_dir = create_temp_dir("create")?;
+            let tar_path = temp_dir.join(inner_tar_name(&archive_path));
+            reporter.begin_phase(0, 50);
+            let result = add_entries(seven_zip_path, &container_switches, &tar_path, &entries, working_dir.as_deref(), reporter, job)
+                .and_then(|mut warnings| {
+                    reporter.begin_phase(50, 50);
+                    let tar_entry = vec![tar_path.to_string_lossy().to_string()];
+                    add_entries(seven_zip_path, &[compressor_switch.to_string()], &archive_path, &tar_entry, None, reporter, job)
+                        .map(|more| {
+                            warnings.extend(more);
+                            warnings
+                        })
+                });
+            if let Err(e) = std::fs::remove_dir_all(&temp_dir) {
+                log_error(&format!("Failed to remove temporary directory '{}': {}", temp_dir.display(), e));
+            }
+            result
+        }
+    };
+
+    let warnings = match result {
+        Ok(warnings) => warnings,
+        Err(error) => {
+            // Never leave a half-written archive behind / 绝不留下写了一半的压缩包
+            if archive_path.exists() {
+                let _ = std::fs::remove_file(&archive_path);
+            }
+            return Err(error);
+        }
+    };
+
+    reporter.finish();
+    log_info(&format!("Created {:?} archive: {} ({} warnings)", format, archive_path.display(), warnings.len()));
+    Ok((archive_path, warnings))
+}
+
+/// Runs `7z a` for a list of entries into the given archive, returning the warnings 7-Zip reported.
+/// `switches` holds the `-t` type switch and any extra switches such as encryption.
+///
+/// 对给定压缩包运行 `7z a` 添加一组条目，并返回 7-Zip 报告的警告。
+/// `switches` 包含 `-t` 类型开关以及加密等额外开关。
+fn add_entries(
+    seven_zip_path: &Path,
+    switches: &[String],
+    archive_path: &Path,
+    entries: &[String],
+    working_dir: Option<&Path>,
+    reporter: &mut ProgressReporter,
+    job: &JobHandle,
+) -> Result<Vec<String>, SoarZipError> {
+    let mut args = vec![
+        "a".to_string(),
+        "-y".to_string(),
+    ];
+    args.extend(switches.iter().cloned());
+    args.push(archive_path.to_string_lossy().to_string());
+    // Stop switch parsing so file names starting with '-' are not taken as switches
+    // 停止开关解析，避免以 '-' 开头的文件名被当作开关
+    args.push("--".to_string());
+    args.extend(entries.iter().cloned());
+
+    let output = run_7z_with_progress(seven_zip_path, &args, working_dir, reporter, job)?;
+    let checked = check_7z_output(&output, "add")?;
+    Ok(checked.warning.into_iter().map(|warning| warning.to_string()).collect())
+}
+
+/// Test-opens a freshly written encrypted archive with `7z t` and its password,
+/// so an archive that cannot be opened with its password is never handed to the user.
+///
+/// 使用 `7z t` 及其密码试打开刚写入的加密压缩包，确保不会把无法使用的压缩包交给用户。
+fn verify_encrypted_archive(
+    seven_zip_path: &Path,
+    archive_path: &Path,
+    password: &str,
+    reporter: &mut ProgressReporter,
+    job: &JobHandle,
+) -> Result<(), SoarZipError> {
+    let mut args = vec!["t".to_string()];
+    args.extend(password_switch(Some(password)));
+    args.push(archive_path.to_string_lossy().to_string());
+    let output = run_7z_with_progress(seven_zip_path, &args, None, reporter, job)?;
+    check_7z_output(&output, "test").map_err(|e| {
+        let error_msg = format!("The encrypted archive could not be verified with its password: {}", e);
+        log_error(&error_msg);
+        SoarZipError::Fatal(error_msg)
+    })?;
+    log_info(&format!("Verified encrypted archive: {}", archive_path.display()));
+    Ok(())
+}
+
+/// Validates the source paths and converts them into 7-Zip entry arguments.
+///
+/// # Returns
+///
+/// * `Ok((Option<PathBuf>, Vec<String>))` - The working directory to run 7-Zip from and the entry arguments.
+/// * `Err(SoarZipError)` - `ArchiveNotFound` if a source is missing, or `InvalidInput` if it lies outside the relative root.
+///
+/// 校验源路径并将其转换为 7-Zip 条目参数。
+///
+/// # 返回值
+///
+/// * `Ok((Option<PathBuf>, Vec<String>))` - 运行 7-Zip 的工作目录以及条目参数。
+/// * `Err(SoarZipError)` - 如果某个源不存在则返回 `ArchiveNotFound`；如果位于相对根目录之外则返回 `InvalidInput`。
+fn resolve_source_entries(
+    source_paths: &[String],
+    relative_root: Option<&Path>,
+) -> Result<(Option<PathBuf>, Vec<String>), SoarZipError> {
+    let canonical_sources = source_paths
+        .iter()
+        .map(|source| {
+            std::fs::canonicalize(source).map_err(|e| {
+                let error_msg = format!("Source path not found: {} ({})", source, e);
+                log_error(&error_msg);
+                SoarZipError::ArchiveNotFound(error_msg)
+            })
+        })
+        .collect::<Result<Vec<PathBuf>, SoarZipError>>()?;
+
+    let root = match relative_root {
+        Some(root) => std::fs::canonicalize(root)
+            .map_err(|e| SoarZipError::InvalidInput(format!("Relative root not found: {} ({})", root.display(), e)))?,
+        None => {
+            let entries = canonical_sources.iter().map(|p| p.to_string_lossy().to_string()).collect();
+            return Ok((None, entries));
+        }
+    };
+
+    let mut entries = Vec::with_capacity(canonical_sources.len());
+    for source in &canonical_sources {
+        let relative = source.strip_prefix(&root).map_err(|_| {
+            let error_msg = format!(
+                "Source path '{}' is not inside the relative root '{}'",
+                source.display(),
+                root.display()
+            );
+            log_error(&error_msg);
+            SoarZipError::InvalidInput(error_msg)
+        })?;
+        if relative.as_os_str().is_empty() {
+            // The root itself was selected: store its contents without an extra top-level folder
+            // 选中的是根目录本身：保存其内容而不额外添加顶层文件夹
+            entries.push("*".to_string());
+        } else {
+            entries.push(relative.to_string_lossy().to_string());
+        }
+    }
+    Ok((Some(root), entries))
+}
+
+/// Turns the target archive path into an absolute path, since 7-Zip may run from another directory.
+///
+/// 将目标压缩包路径转换为绝对路径，因为 7-Zip 可能在其他目录中运行。
+fn absolute_target_path(archive_path: &Path) -> Result<PathBuf, SoarZipError> {
+    let file_name = archive_path
+        .file_name()
+        .ok_or_else(|| SoarZipError::InvalidInput(format!("Invalid archive path: {}", archive_path.display())))?;
+    let parent = match archive_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let parent = std::fs::canonicalize(&parent)
+        .map_err(|e| SoarZipError::InvalidInput(format!("Target directory not found: {} ({})", parent.display(), e)))?;
+    Ok(parent.join(file_name))
+}
+
+/// Derives the name of the intermediate tar from a compressed tarball name
+/// (e.g., `backup.tar.gz` -> `backup.tar`, `backup.tgz` -> `backup.tar`).
+///
+/// 从压缩 tar 包名称推导中间 tar 的名称
+/// （例如 `backup.tar.gz` -> `backup.tar`，`backup.tgz` -> `backup.tar`）。
+fn inner_tar_name(archive_path: &Path) -> String {
+    let stem = archive_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "archive".to_string());
+    if stem.to_lowercase().ends_with(".tar") {
+        stem
+    } else {
+        format!("{}.tar", stem)
+    }
+}
+
+/// Sums the sizes of all files below the given paths, used as the progress total.
+/// Unreadable paths are counted as zero rather than failing the operation. Symbolic links are
+/// counted themselves and not followed, so a link back to a parent folder cannot loop.
+///
+/// 累加给定路径下所有文件的大小，用作进度总量。
+/// 无法读取的路径按零计算，而不会使操作失败。符号链接只计算其自身而不跟随，因此指向上级文件夹的链接不会造成循环。
+pub fn total_source_size(source_paths: &[String]) -> u64 {
+    fn size_of(path: &Path) -> u64 {
+        match std::fs::symlink_metadata(path) {
+            Ok(meta) if meta.is_dir() => std::fs::read_dir(path)
+                .map(|entries| entries.flatten().fold(0u64, |sum, entry| sum.saturating_add(size_of(&entry.path()))))
+                .unwrap_or(0),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        }
+    }
+    source_paths.iter().fold(0u64, |sum, p| sum.saturating_add(size_of(Path::new(p))))
+}
@@ -0,0 +1,614 @@
+//! Modification of existing archives (adding, deleting, renaming and updating entries).
+//! Every change is written to a temporary copy that replaces the original only on success.
+//!
+//! 对现有压缩包的修改（添加、删除、重命名和更新条目）。
+//! 每次修改都写入临时副本，仅在成功后才替换原文件。
+
+use std::collections::{HashMap, HashSet};
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use serde::{Serialize, Deserialize};
+
+use super::file_item::FileItem;
+use super::logging::{log_info, log_error};
+use super::archive_utils::{
+    run_7z_command, run_7z_command_in_dir, check_7z_output, create_temp_dir, password_switch, run_with_list_file, select_entries,
+};
+use super::error::SoarZipError;
+
+/// Numbers the working copies of this process, so concurrent modifications never share one
+/// 为本进程的工作副本编号，使并发的修改永远不会共用同一个副本
+static WORKING_COPY_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// One lock per archive, held around the whole list, modify and swap sequence of an edit.
+/// Managed as Tauri state.
+///
+/// Without it, two edits of the same archive (e.g. an add while an opened entry is written back)
+/// would both start from the same original, and the second swap would drop the first change.
+///
+/// 每个压缩包一把锁，在一次编辑的"列出、修改、替换"整个过程中持有。作为 Tauri 状态进行管理。
+///
+/// 如果没有它，对同一压缩包的两次编辑（例如在写回已打开条目的同时添加文件）会从同一个原文件开始，
+/// 第二次替换会丢掉第一次的更改。
+#[derive(Default)]
+pub struct ArchiveLocks {
+    locks: Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>,
+}
+
+impl ArchiveLocks {
+    /// Returns the lock of an archive; different spellings of the same path share one lock.
+    ///
+    /// 返回压缩包的锁；同一路径的不同写法共用一把锁。
+    pub fn lock_for(&self, archive_path: &Path) -> Arc<Mutex<()>> {
+        let key = std::fs::canonicalize(archive_path).unwrap_or_else(|_| archive_path.to_path_buf());
+        Arc::clone(self.locks.lock().unwrap().entry(key).or_default())
+    }
+}
+
+/// How to handle a file whose destination path already exists inside the archive.
+/// 当目标路径在压缩包内已存在时的处理方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddConflictPolicy {
+    /// Overwrite the existing entry with the new file. / 用新文件覆盖现有条目。
+    Replace,
+    /// Keep the existing entry and do not add the new file. / 保留现有条目，不添加新文件。
+    Skip,
+    /// Add the new file under a free name such as `name (1).ext`. / 以 `name (1).ext` 之类的空闲名称添加新文件。
+    KeepBoth,
+}
+
+/// A file or directory on disk paired with the path it will get inside the archive.
+/// 磁盘上的文件或目录及其在压缩包内的目标路径。
+struct PlannedEntry {
+    source: PathBuf,
+    archive_name: String,
+    is_dir: bool,
+}
+
+/// Adds files and folders from disk to an existing archive below `target_dir`.
+///
+/// 7-Zip has no switch for choosing a destination folder inside the archive, so the sources are
+/// laid out in a staging directory under their final archive paths (hard-linked where possible,
+/// copied otherwise) and added from there.
+///
+/// # Arguments
+///
+/// * `seven_zip_path` - The path to the 7-Zip executable.
+/// * `archive_path` - The archive to update.
+/// * `existing_entries` - The current listing of the archive, used to detect conflicts.
+/// * `target_dir` - The folder inside the archive to add to ("" for the root).
+/// * `source_paths` - Files and folders on disk to add.
+/// * `policy` - How to resolve conflicts with existing entries.
+///
+/// # Returns
+///
+/// * `Ok(usize)` - The number of files and folders that were written to the archive.
+/// * `Err(SoarZipError)` - The error if validation, staging or 7-Zip fails.
+///
+/// 将磁盘上的文件和文件夹添加到现有压缩包的 `target_dir` 下。
+///
+/// 7-Zip 没有用于选择压缩包内目标文件夹的开关，因此会先在暂存目录中按最终的压缩包路径
+/// 布置源文件（尽可能使用硬链接，否则复制），然后从该目录添加。
+///
+/// # 参数
+///
+/// * `seven_zip_path` - 7-Zip 可执行文件的路径。
+/// * `archive_path` - 要更新的压缩包。
+/// * `existing_entries` - 压缩包的当前列表，用于检测冲突。
+/// * `target_dir` - 压缩包内要添加到的文件夹（"" 表示根目录）。
+/// * `source_paths` - 要添加的磁盘文件和文件夹。
+/// * `policy` - 如何解决与现有条目的冲突。
+///
+/// # 返回值
+///
+/// * `Ok(usize)` - 写入压缩包的文件和文件夹数量。
+/// * `Err(SoarZipError)` - 如果校验、暂存或 7-Zip 失败，则返回相应错误。
+pub fn add_to_archive_with_7z(
+    seven_zip_path: &Path,
+    archive_path: &Path,
+    existing_entries: &[FileItem],
+    target_dir: &str,
+    source_paths: &[String],
+    policy: AddConflictPolicy,
+) -> Result<usize, SoarZipError> {
+    if source_paths.is_empty() {
+        return Err(SoarZipError::InvalidInput("No files were given to add to the archive.".to_string()));
+    }
+    let target_prefix = normalize_archive_dir(target_dir)?;
+
+    let mut taken_names: HashSet<String> = existing_entries
+        .iter()
+        .map(|item| item.name.trim_end_matches('/').to_string())
+        .collect();
+
+    let mut planned = Vec::new();
+    for source in source_paths {
+        let source_path = PathBuf::from(source);
+        let file_name = source_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .ok_or_else(|| SoarZipError::InvalidInput(format!("Invalid source path: {}", source)))?;
+        if !source_path.exists() {
+            let error_msg = format!("Source path not found: {}", source);
+            log_error(&error_msg);
+            return Err(SoarZipError::ArchiveNotFound(error_msg));
+        }
+        plan_entries(&source_path, &format!("{}{}", target_prefix, file_name), policy, &mut taken_names, &mut planned)?;
+    }
+
+    if planned.is_empty() {
+        log_info("Nothing to add: every source conflicted with an existing entry and was skipped.");
+        return Ok(0);
+    }
+
+    let staging_dir = create_temp_dir("add")?;
+    let result = stage_entries(&planned, &staging_dir).and_then(|top_level| {
+        modify_archive_atomically(archive_path, |working_copy| {
+            let mut args = vec![
+                "a".to_string(),
+                "-y".to_string(),
+                working_copy.to_string_lossy().to_string(),
+                "--".to_string(),
+            ];
+            args.extend(top_level);
+            let output = run_7z_command_in_dir(seven_zip_path, &args, Some(&staging_dir))?;
+            check_7z_output(&output, "add").map(|_| ())
+        })
+    });
+    if let Err(e) = std::fs::remove_dir_all(&staging_dir) {
+        log_error(&format!("Failed to remove staging directory '{}': {}", staging_dir.display(), e));
+    }
+    result?;
+
+    log_info(&format!("Added {} item(s) to archive: {}", planned.len(), archive_path.display()));
+    Ok(planned.len())
+}
+
+/// Normalizes a folder path inside the archive to the `a/b/` form ("" for the root),
+/// rejecting components that would escape the archive root.
+///
+/// 将压缩包内的文件夹路径规范化为 `a/b/` 形式（根目录为 ""），
+/// 并拒绝会逃出压缩包根目录的路径组件。
+fn normalize_archive_dir(dir: &str) -> Result<String, SoarZipError> {
+    let mut normalized = String::new();
+    for component in dir.replace('\\', "/").split('/') {
+        match component {
+            "" | "." => continue,
+            ".." => return Err(SoarZipError::InvalidInput(format!("Invalid folder inside archive: {}", dir))),
+            name => {
+                normalized.push_str(name);
+                normalized.push('/');
+            }
+        }
+    }
+    Ok(normalized)
+}
+
+/// Walks a source path and records where each file and folder goes inside the archive,
+/// applying the conflict policy to files that already exist.
+///
+/// 遍历源路径并记录每个文件和文件夹在压缩包内的位置，对已存在的文件应用冲突策略。
+fn plan_entries(
+    source: &Path,
+    archive_name: &str,
+    policy: AddConflictPolicy,
+    taken_names: &mut HashSet<String>,
+    planned: &mut Vec<PlannedEntry>,
+) -> Result<(), SoarZipError> {
+    if source.is_dir() {
+        // Directories merge with existing ones; only files can conflict
+        // 目录会与现有目录合并，只有文件会产生冲突
+        taken_names.insert(archive_name.to_string());
+        planned.push(PlannedEntry { source: source.to_path_buf(), archive_name: archive_name.to_string(), is_dir: true });
+
+        let read_dir = std::fs::read_dir(source)
+            .map_err(|e| SoarZipError::Io(format!("Failed to read directory '{}': {}", source.display(), e)))?;
+        for child in read_dir {
+            let child = child.map_err(|e| SoarZipError::Io(format!("Failed to read directory '{}': {}", source.display(), e)))?;
+            let child_name = child.file_name().to_string_lossy().to_string();
+            plan_entries(&child.path(), &format!("{}/{}", archive_name, child_name), policy, taken_names, planned)?;
+        }
+        return Ok(());
+    }
+
+    let final_name = if !taken_names.contains(archive_name) {
+        archive_name.to_string()
+    } else {
+        match policy {
+            AddConflictPolicy::Replace => archive_name.to_string(),
+            AddConflictPolicy::Skip => {
+                log_info(&format!("Skipping existing entry: {}", archive_name));
+                return Ok(());
+            }
+            AddConflictPolicy::KeepBoth => unique_archive_name(archive_name, taken_names),
+        }
+    };
+    taken_names.insert(final_name.clone());
+    planned.push(PlannedEntry { source: source.to_path_buf(), archive_name: final_name, is_dir: false });
+    Ok(())
+}
+
+/// Finds a free name of the form `name (n).ext` next to `archive_name`.
+///
+/// 在 `archive_name` 旁边找到一个 `name (n).ext` 形式的空闲名称。
+pub fn unique_archive_name(archive_name: &str, taken_names: &HashSet<String>) -> String {
+    let (parent, file_name) = match archive_name.rfind('/') {
+        Some(idx) => (&archive_name[..=idx], &archive_name[idx + 1..]),
+        None => ("", archive_name),
+    };
+    // Leading dots (e.g. ".gitignore") belong to the name, not to an extension
+    // 前导点（例如 ".gitignore"）属于名称而不是扩展名
+    let (stem, ext) = match file_name.rfind('.') {
+        Some(idx) if idx > 0 => (&file_name[..idx], &file_name[idx..]),
+        _ => (file_name, ""),
+    };
+    (1..)
+        .map(|n| format!("{}{} ({}){}", parent, stem, n, ext))
+        .find(|candidate| !taken_names.contains(candidate))
+        .expect("an unbounded counter always yields a free name")
+}
+
+/// Recreates the planned entries under `staging_dir` and returns the top-level names to pass to 7-Zip.
+///
+/// 在 `staging_dir` 下重建计划的条目，并返回要传给 7-Zip 的顶层名称。
+fn stage_entries(planned: &[PlannedEntry], staging_dir: &Path) -> Result<Vec<String>, SoarZipError> {
+    let mut top_level = Vec::new();
+    for entry in planned {
+        let staged_path = staging_dir.join(&entry.archive_name);
+        let stage_error = |e: std::io::Error| SoarZipError::Io(format!("Failed to stage '{}': {}", entry.source.display(), e));
+        if entry.is_dir {
+            std::fs::create_dir_all(&staged_path).map_err(stage_error)?;
+        } else {
+            if let Some(parent) = staged_path.parent() {
+                std::fs::create_dir_all(parent).map_err(stage_error)?;
+            }
+            // Hard links avoid copying large files; they fail across filesystems, so fall back to a copy
+            // 硬链接可避免复制大文件；跨文件系统时会失败，因此回退为复制
+            if std::fs::hard_link(&entry.source, &staged_path).is_err() {
+                std::fs::copy(&entry.source, &staged_path).map_err(stage_error)?;
+            }
+        }
+
+        let top = entry.archive_name.split('/').next().unwrap_or_default().to_string();
+        if !top.is_empty() && !top_level.contains(&top) {
+            top_level.push(top);
+        }
+    }
+    Ok(top_level)
+}
+
+/// Deletes entries from an archive. Selected folders are removed together with everything below them.
+///
+/// # Arguments
+///
+/// * `seven_zip_path` - The path to the 7-Zip executable.
+/// * `archive_path` - The archive to update.
+/// * `existing_entries` - The current listing of the archive, used to expand folders.
+/// * `paths_to_delete` - Entry paths inside the archive; folders may end with '/'.
+///
+/// # Returns
+///
+/// * `Ok(usize)` - The number of archive entries passed to 7-Zip for deletion.
+/// * `Err(SoarZipError)` - `InvalidInput` if nothing matches, or the 7-Zip error.
+///
+/// 从压缩包中删除条目。选中的文件夹会连同其下所有内容一起删除。
+///
+/// # 参数
+///
+/// * `seven_zip_path` - 7-Zip 可执行文件的路径。
+/// * `archive_path` - 要更新的压缩包。
+/// * `existing_entries` - 压缩包的当前列表，用于展开文件夹。
+/// * `paths_to_delete` - 压缩包内的条目路径；文件夹可以以 '/' 结尾。
+///
+/// # 返回值
+///
+/// * `Ok(usize)` - 传给 7-Zip 删除的压缩包条目数量。
+/// * `Err(SoarZipError)` - 如果没有匹配项则返回 `InvalidInput`；否则返回 7-Zip 错误。
+pub fn delete_from_archive_with_7z(
+    seven_zip_path: &Path,
+    archive_path: &Path,
+    existing_entries: &[FileItem],
+    paths_to_delete: &[String],
+) -> Result<usize, SoarZipError> {
+    if paths_to_delete.is_empty() {
+        return Err(SoarZipError::InvalidInput("No entries were selected for deletion.".to_string()));
+    }
+    // Expand folders into explicit entry names instead of using `-r`, which would also match
+    // equally named files in unrelated subfolders.
+    // 将文件夹展开为明确的条目名称，而不是使用 `-r`，因为后者还会匹配无关子文件夹中的同名文件。
+    let names: Vec<String> = select_entries(existing_entries, paths_to_delete)
+        .iter()
+        .map(|item| item.name.trim_end_matches('/').to_string())
+        .collect();
+    if names.is_empty() {
+        return Err(SoarZipError::InvalidInput(format!("None of the selected entries exist in the archive: {:?}", paths_to_delete)));
+    }
+
+    let count = names.len();
+    run_with_list_file(&names, |list_file_arg| {
+        modify_archive_atomically(archive_path, |working_copy| {
+            let args = vec![
+                "d".to_string(),
+                "-y".to_string(),
+                "-scsUTF-8".to_string(),
+                // Names such as '*.txt' are taken literally, never as wildcards / '*.txt' 这样的名称按字面处理，绝不作为通配符
+                "-spd".to_string(),
+                working_copy.to_string_lossy().to_string(),
+                list_file_arg.to_string(),
+            ];
+            let output = run_7z_command(seven_zip_path, &args)?;
+            check_7z_output(&output, "delete").map(|_| ())
+        })
+    })?;
+
+    log_info(&format!("Deleted {} entries from archive: {}", count, archive_path.display()));
+    Ok(count)
+}
+
+/// Renames or moves an entry inside an archive.
+///
+/// For a folder, every entry below it is renamed as well (`docs/a.txt` -> `manual/a.txt`),
+/// because 7-Zip's `rn` only matches the exact names it is given.
+///
+/// # Arguments
+///
+/// * `seven_zip_path` - The path to the 7-Zip executable.
+/// * `archive_path` - The archive to update.
+/// * `existing_entries` - The current listing of the archive, used to expand folders and detect collisions.
+/// * `old_path` - The current entry path inside the archive; folders may end with '/'.
+/// * `new_path` - The new entry path inside the archive.
+///
+/// # Returns
+///
+/// * `Ok(usize)` - The number of archive entries renamed.
+/// * `Err(SoarZipError)` - `InvalidInput` if the paths are invalid or the target collides with an existing entry, or the 7-Zip error.
+///
+/// 重命名或移动压缩包内的条目。
+///
+/// 对于文件夹，其下的所有条目也会被重命名（`docs/a.txt` -> `manual/a.txt`），
+/// 因为 7-Zip 的 `rn` 只匹配给定的确切名称。
+///
+/// # 参数
+///
+/// * `seven_zip_path` - 7-Zip 可执行文件的路径。
+/// * `archive_path` - 要更新的压缩包。
+/// * `existing_entries` - 压缩包的当前列表，用于展开文件夹和检测冲突。
+/// * `old_path` - 压缩包内当前的条目路径；文件夹可以以 '/' 结尾。
+/// * `new_path` - 压缩包内新的条目路径。
+///
+/// # 返回值
+///
+/// * `Ok(usize)` - 被重命名的压缩包条目数量。
+/// * `Err(SoarZipError)` - 如果路径无效或目标与现有条目冲突则返回 `InvalidInput`；否则返回 7-Zip 错误。
+pub fn rename_in_archive_with_7z(
+    seven_zip_path: &Path,
+    archive_path: &Path,
+    existing_entries: &[FileItem],
+    old_path: &str,
+    new_path: &str,
+) -> Result<usize, SoarZipError> {
+    let old_name = normalize_archive_dir(old_path)?.trim_end_matches('/').to_string();
+    let new_name = normalize_archive_dir(new_path)?.trim_end_matches('/').to_string();
+    if old_name.is_empty() || new_name.is_empty() {
+        return Err(SoarZipError::InvalidInput("Both the old and the new name must be non-empty paths inside the archive.".to_string()));
+    }
+    if old_name == new_name {
+        return Ok(0);
+    }
+
+    let old_prefix = format!("{}/", old_name);
+    let new_prefix = format!("{}/", new_name);
+    if new_prefix.starts_with(&old_prefix) {
+        return Err(SoarZipError::InvalidInput(format!("Cannot move '{}' into itself ('{}').", old_name, new_name)));
+    }
+
+    let mut pairs: Vec<(String, String)> = Vec::new();
+    for item in existing_entries {
+        let item_bare = item.name.trim_end_matches('/');
+        if item_bare == old_name {
+            pairs.push((item_bare.to_string(), new_name.clone()));
+        } else if let Some(rest) = item_bare.strip_prefix(&old_prefix) {
+            pairs.push((item_bare.to_string(), format!("{}{}", new_prefix, rest)));
+        }
+    }
+    if pairs.is_empty() {
+        return Err(SoarZipError::InvalidInput(format!("Entry not found in archive: {}", old_path)));
+    }
+
+    if let Some(existing) = existing_entries.iter().find(|item| {
+        let item_bare = item.name.trim_end_matches('/');
+        item_bare == new_name || item_bare.starts_with(&new_prefix)
+    }) {
+        let error_msg = format!("Cannot rename '{}' to '{}': '{}' already exists in the archive.", old_name, new_name, existing.name);
+        log_error(&error_msg);
+        return Err(SoarZipError::InvalidInput(error_msg));
+    }
+
+    // `rn` reads old/new names from a list file as alternating lines
+    // `rn` 从列表文件中按交替行读取旧名称/新名称
+    let lines: Vec<String> = pairs.iter().flat_map(|(old, new)| [old.clone(), new.clone()]).collect();
+    run_with_list_file(&lines, |list_file_arg| {
+        modify_archive_atomically(archive_path, |working_copy| {
+            let args = vec![
+                "rn".to_string(),
+                "-y".to_string(),
+                "-scsUTF-8".to_string(),
+                // Names such as '[draft].md' are taken literally, never as wildcards / '[draft].md' 这样的名称按字面处理，绝不作为通配符
+                "-spd".to_string(),
+                working_copy.to_string_lossy().to_string(),
+                list_file_arg.to_string(),
+            ];
+            let output = run_7z_command(seven_zip_path, &args)?;
+            check_7z_output(&output, "rename").map(|_| ())
+        })
+    })?;
+
+    log_info(&format!("Renamed '{}' to '{}' ({} entries) in archive: {}", old_name, new_name, pairs.len(), archive_path.display()));
+    Ok(pairs.len())
+}
+
+/// Replaces the content of one file entry with a file from disk, e.g. an opened entry that was
+/// edited in another application.
+///
+/// Like adding, the file is staged under its archive path and written with `7z u`, which
+/// replaces the entry in place and keeps every other entry as it is.
+///
+/// # Arguments
+///
+/// * `seven_zip_path` - The path to the 7-Zip executable.
+/// * `archive_path` - The archive to update.
+/// * `entry_path` - The path of the file entry inside the archive.
+/// * `source_file` - The file on disk holding the new content.
+/// * `password` - Optional password for encrypted archives; the updated entry is encrypted with it as well.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the archive was updated.
+/// * `Err(SoarZipError)` - `InvalidInput` for an invalid entry path, or the error if staging or 7-Zip fails.
+///
+/// 用磁盘上的文件替换一个文件条目的内容，例如在其他应用程序中编辑过的已打开条目。
+///
+/// 与添加相同，文件先按其压缩包路径暂存，然后使用 `7z u` 写入，它会就地替换该条目并保持其他条目不变。
+///
+/// # 参数
+///
+/// * `seven_zip_path` - 7-Zip 可执行文件的路径。
+/// * `archive_path` - 要更新的压缩包。
+/// * `entry_path` - 文件条目在压缩包内的路径。
+/// * `source_file` - 保存新内容的磁盘文件。
+/// * `password` - 用于加密压缩包的可选密码；更新后的条目也会用它加密。
+///
+/// # 返回值
+///
+/// * `Ok(())` - 如果压缩包已更新。
+/// * `Err(SoarZipError)` - 条目路径无效时返回 `InvalidInput`；暂存或 7-Zip 失败时返回相应错误。
+pub fn update_entry_with_7z(
+    seven_zip_path: &Path,
+    archive_path: &Path,
+    entry_path: &str,
+    source_file: &Path,
+    password: Option<&str>,
+) -> Result<(), SoarZipError> {
+    let entry_name = normalize_archive_dir(entry_path)?.trim_end_matches('/').to_string();
+    if entry_name.is_empty() {
+        return Err(SoarZipError::InvalidInput(format!("Invalid entry path: {}", entry_path)));
+    }
+    if !source_file.is_file() {
+        let error_msg = format!("Updated file not found: {}", source_file.display());
+        log_error(&error_msg);
+        return Err(SoarZipError::ArchiveNotFound(error_msg));
+    }
+
+    let staging_dir = create_temp_dir("update")?;
+    let staged_file = staging_dir.join(&entry_name);
+    let staged = staged_file
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::copy(source_file, &staged_file))
+        .map_err(|e| SoarZipError::Io(format!("Failed to stage '{}' for updating: {}", source_file.display(), e)));
+    let result = staged.and_then(|_| {
+        modify_archive_atomically(archive_path, |working_copy| {
+            let mut args = vec!["u".to_string(), "-y".to_string()];
+            args.extend(password_switch(password));
+            args.push(working_copy.to_string_lossy().to_string());
+            args.push("--".to_string());
+            args.push(entry_name.clone());
+            let output = run_7z_command_in_dir(seven_zip_path, &args, Some(&staging_dir))?;
+            check_7z_output(&output, "update").map(|_| ())
+        })
+    });
+    if let Err(e) = std::fs::remove_dir_all(&staging_dir) {
+        log_error(&format!("Failed to remove staging directory '{}': {}", staging_dir.display(), e));
+    }
+    result?;
+
+    log_info(&format!("Updated entry '{}' in archive: {}", entry_name, archive_path.display()));
+    Ok(())
+}
+
+/// Applies a 7-Zip modification to a temporary copy of the archive and swaps it in atomically.
+///
+/// The copy lives in the same directory as the archive so the final rename stays on one
+/// filesystem. If `operation` fails, the copy is removed and the original is left untouched,
+/// so a crash or error never leaves a half-written archive in place.
+///
+/// # Arguments
+///
+/// * `archive_path` - The archive to modify.
+/// * `operation` - Performs the modification on the path of the working copy.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the operation succeeded and the archive was replaced.
+/// * `Err(SoarZipError)` - The error if copying, the operation or the swap fails.
+///
+/// 对压缩包的临时副本执行 7-Zip 修改，并以原子方式替换原文件。
+///
+/// 副本与压缩包位于同一目录，使最终的重命名保持在同一文件系统内。如果 `operation` 失败，
+/// 副本会被删除且原文件保持不变，因此崩溃或错误永远不会留下写了一半的压缩包。
+///
+/// # 参数
+///
+/// * `archive_path` - 要修改的压缩包。
+/// * `operation` - 对工作副本路径执行修改。
+///
+/// # 返回值
+///
+/// * `Ok(())` - 如果操作成功且压缩包已被替换。
+/// * `Err(SoarZipError)` - 如果复制、操作或替换失败，则返回相应错误。
+pub fn modify_archive_atomically<F>(archive_path: &Path, operation: F) -> Result<(), SoarZipError>
+where
+    F: FnOnce(&Path) -> Result<(), SoarZipError>,
+{
+    let file_name = archive_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| SoarZipError::InvalidInput(format!("Invalid archive path: {}", archive_path.display())))?;
+    let working_copy = create_working_copy_file(archive_path, &file_name)?;
+
+    let result = std::fs::copy(archive_path, &working_copy)
+        .map_err(|e| {
+            let error_msg = format!("Failed to create working copy of '{}': {}", archive_path.display(), e);
+            log_error(&error_msg);
+            SoarZipError::Io(error_msg)
+        })
+        .and_then(|_| operation(&working_copy))
+        .and_then(|_| {
+            std::fs::rename(&working_copy, archive_path).map_err(|e| {
+                let error_msg = format!("Failed to replace archive '{}': {}", archive_path.display(), e);
+                log_error(&error_msg);
+                SoarZipError::Io(error_msg)
+            })
+        });
+    if result.is_err() && working_copy.exists() {
+        let _ = std::fs::remove_file(&working_copy);
+    }
+    result
+}
+
+/// Reserves a working copy next to the archive under a name no other modification uses:
+/// `.soarzip-tmp-<pid>-<n>-<name>`, created with `create_new` so an existing file is never reused.
+///
+/// 在压缩包旁边以其他修改不会使用的名称预留工作副本：`.soarzip-tmp-<pid>-<n>-<名称>`，
+/// 使用 `create_new` 创建，因此永远不会复用已存在的文件。
+fn create_working_copy_file(archive_path: &Path, file_name: &str) -> Result<PathBuf, SoarZipError> {
+    loop {
+        // Keep the original extension at the end so 7-Zip detects the same format
+        // 保留原扩展名在末尾，使 7-Zip 识别为相同格式
+        let counter = WORKING_COPY_COUNTER.fetch_add(1, Ordering::SeqCst);
+        let working_copy = archive_path.with_file_name(format!(".soarzip-tmp-{}-{}-{}", std::process::id(), counter, file_name));
+        match OpenOptions::new().write(true).create_new(true).open(&working_copy) {
+            Ok(_) => return Ok(working_copy),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                let error_msg = format!("Failed to create working copy of '{}': {}", archive_path.display(), e);
+                log_error(&error_msg);
+                return Err(SoarZipError::Io(error_msg));
+            }
+        }
+    }
+}
@@ -0,0 +1,1156 @@
+//! Options, safety checks and results of extracting archives.
+//! 解压压缩包的选项、安全检查和结果。
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+use serde::{Serialize, Deserialize};
+
+use super::file_item::FileItem;
+use super::logging::{log_info, log_error};
+use super::archive_utils::{new_7z_command, password_switch, spawn_error};
+use super::jobs::JobHandle;
+use super::error::SoarZipError;
+
+/// Entries smaller than this are not checked against the compression ratio limit,
+/// since tiny, highly repetitive files legitimately compress extremely well.
+/// 小于此大小的条目不检查压缩比限制，因为微小且高度重复的文件本来就能被极度压缩。
+const RATIO_CHECK_MIN_SIZE: u64 = 1024 * 1024;
+
+/// How often the output size is measured while 7-Zip extracts / 7-Zip 解压期间测量输出大小的间隔
+const WATCHDOG_INTERVAL: Duration = Duration::from_millis(500);
+
+/// What to do with entries whose paths would be written outside the output directory.
+/// 如何处理路径会写到输出目录之外的条目。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnsafePathPolicy {
+    /// Extract nothing and fail with `UNSAFE_ENTRY_PATHS` / 不解压任何内容，并以 `UNSAFE_ENTRY_PATHS` 失败
+    #[default]
+    Refuse,
+    /// Leave the unsafe entries out and extract the rest / 跳过不安全的条目，解压其余内容
+    Skip,
+    /// Extract unsafe entries under a cleaned-up path inside the output directory;
+    /// symbolic links that escape are still left out
+    /// 将不安全的条目以清理后的路径解压到输出目录内；越界的符号链接仍会被跳过
+    Sanitize,
+}
+
+/// Why an entry was considered unsafe.
+/// 条目被视为不安全的原因。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnsafePathReason {
+    /// The path starts at the file system root (e.g., `/etc/passwd`) / 路径从文件系统根目录开始（例如 `/etc/passwd`）
+    AbsolutePath,
+    /// The path starts with a drive letter (e.g., `C:\Windows`) / 路径以驱动器号开头（例如 `C:\Windows`）
+    DriveLetter,
+    /// The path contains a `..` component / 路径包含 `..` 组成部分
+    ParentTraversal,
+    /// The entry is a symbolic link pointing outside the output directory / 条目是指向输出目录之外的符号链接
+    SymlinkEscape,
+    /// The path passes through a symbolic link stored in the archive / 路径经过压缩包中存储的符号链接
+    ThroughSymlink,
+}
+
+/// An entry that was not extracted as stored.
+/// 一个未按原样解压的条目。
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockedEntry {
+    /// The path of the entry inside the archive.
+    pub path: String,
+    /// Why the entry is unsafe.
+    pub reason: UnsafePathReason,
+    /// The path the entry was extracted to instead, relative to the output directory.
+    /// `None` if the entry was not extracted at all.
+    pub sanitized_path: Option<String>,
+}
+
+/// What to do when an extracted file already exists in the output directory.
+/// 当解压的文件已存在于输出目录中时如何处理。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverwritePolicy {
+    /// Replace existing files (`-aoa`) / 替换已存在的文件（`-aoa`）
+    OverwriteAll,
+    /// Keep existing files and leave those entries out (`-aos`) / 保留已存在的文件，跳过这些条目（`-aos`）
+    SkipExisting,
+    /// Extract under a new name such as `report_1.txt` (`-aou`) / 以新名称（例如 `report_1.txt`）解压（`-aou`）
+    RenameExtracted,
+    /// Rename the existing file and extract under the original name (`-aot`) / 重命名已存在的文件，并以原名称解压（`-aot`）
+    RenameExisting,
+    /// Write nothing if anything exists; return the conflicting paths so the user can decide
+    /// 如有任何文件已存在则不写入任何内容；返回冲突的路径供用户决定
+    #[default]
+    Ask,
+}
+
+impl OverwritePolicy {
+    /// The 7-Zip overwrite switch. `Ask` only gets here when there are no conflicts,
+    /// so it skips anything that appeared in the meantime rather than overwriting it.
+    ///
+    /// 7-Zip 的覆盖开关。只有在没有冲突时 `Ask` 才会到达这里，
+    /// 因此它会跳过期间新出现的文件，而不是覆盖它们。
+    pub fn switch(self) -> &'static str {
+        match self {
+            OverwritePolicy::OverwriteAll => "-aoa",
+            OverwritePolicy::SkipExisting | OverwritePolicy::Ask => "-aos",
+            OverwritePolicy::RenameExtracted => "-aou",
+            OverwritePolicy::RenameExisting => "-aot",
+        }
+    }
+}
+
+/// Where the entries go relative to the chosen output directory.
+/// 条目相对于所选输出目录的存放位置。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractLayout {
+    /// Extract into the output directory as given / 按原样解压到给定的输出目录
+    #[default]
+    Direct,
+    /// "Extract here": extract directly if everything lives under one top-level entry,
+    /// otherwise into a new subfolder named after the archive
+    /// "解压到此处"：如果所有内容都位于同一个顶层条目之下则直接解压，否则解压到以压缩包命名的新子文件夹中
+    Smart,
+}
+
+/// How the folder structure stored in the archive is reproduced in the output directory.
+/// 如何在输出目录中重现压缩包中存储的文件夹结构。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathMode {
+    /// Keep the full stored paths (`7z x`) / 保留完整的存储路径（`7z x`）
+    #[default]
+    Full,
+    /// Keep only the part of the path below [`ExtractOptions::base_path`], usually the folder being
+    /// browsed: `docs/img/a.png` extracted relative to `docs` becomes `img/a.png`
+    /// 只保留 [`ExtractOptions::base_path`]（通常是正在浏览的文件夹）之下的路径部分：
+    /// 相对于 `docs` 解压 `docs/img/a.png` 会得到 `img/a.png`
+    Relative,
+    /// Put every selected file directly into the output directory (`7z e`); files with the
+    /// same name get a `_N` suffix instead of replacing each other
+    /// 将每个所选文件直接放入输出目录（`7z e`）；同名文件会获得 `_N` 后缀，而不是相互替换
+    Flatten,
+}
+
+/// Resource limits that protect against decompression bombs. `None` disables a limit.
+/// 防止解压炸弹的资源限制。`None` 表示禁用该限制。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ExtractionLimits {
+    /// The largest total unpacked size of the extracted entries, in bytes.
+    pub max_total_size: Option<u64>,
+    /// The largest unpacked-to-packed size ratio of a single entry (e.g., 1000 means 1000:1).
+    pub max_compression_ratio: Option<f64>,
+    /// The largest number of entries extracted at once.
+    pub max_entries: Option<usize>,
+    /// The deepest folder path of an entry (`a/b/c.txt` has depth 3).
+    pub max_path_depth: Option<usize>,
+    /// The most archives opened inside one another (`a.7z!/b.zip!/c.tar` has depth 2).
+    pub max_nesting_depth: Option<usize>,
+}
+
+impl Default for ExtractionLimits {
+    fn default() -> Self {
+        ExtractionLimits {
+            max_total_size: Some(64 * 1024 * 1024 * 1024),
+            max_compression_ratio: Some(1000.0),
+            max_entries: Some(1_000_000),
+            max_path_depth: Some(64),
+            max_nesting_depth: Some(8),
+        }
+    }
+}
+
+/// Options of an extraction. Every field has a safe default, so the frontend may omit any of them.
+/// 解压选项。每个字段都有安全的默认值，因此前端可以省略其中任何一个。
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ExtractOptions {
+    /// How entries with unsafe paths are handled.
+    pub unsafe_paths: UnsafePathPolicy,
+    /// Limits checked against the listing before extraction and against the written data during it.
+    pub limits: ExtractionLimits,
+    /// Bytes that must remain free on the destination disk after extraction, on top of the unpacked size.
+    pub disk_space_margin: u64,
+    /// What to do with files that already exist in the output directory.
+    pub overwrite: OverwritePolicy,
+    /// Whether to extract directly or let the entries decide about a subfolder.
+    pub layout: ExtractLayout,
+    /// Whether stored paths are kept in full, relative to `base_path`, or dropped.
+    pub path_mode: PathMode,
+    /// The folder inside the archive that [`PathMode::Relative`] paths start from; empty for the root.
+    pub base_path: String,
+}
+
+/// An entry that will be written, and where.
+/// 一个将被写入的条目及其写入位置。
+#[derive(Debug, Clone)]
+pub struct PlannedEntry<'a> {
+    /// The entry in the archive listing.
+    pub item: &'a FileItem,
+    /// The path the entry is written to, relative to the output directory.
+    pub target: String,
+    /// Whether the entry is extracted on its own rather than by the main 7-Zip run,
+    /// because 7-Zip would not write it to `target` (sanitized entries, renamed flattened files).
+    pub individual: bool,
+}
+
+/// What an extraction did, returned to the frontend.
+/// 解压的执行结果，返回给前端。
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExtractionReport {
+    /// The directory the entries were extracted to.
+    pub output_directory: String,
+    /// Entries with unsafe paths that were skipped or sanitized.
+    pub blocked_entries: Vec<BlockedEntry>,
+    /// With the `ask` policy, the entries whose files already exist in the output directory.
+    /// When this is not empty, nothing was extracted.
+    pub conflicts: Vec<String>,
+}
+
+/// Scans entries for paths that would be written outside the output directory:
+/// absolute paths, drive letters, `..` components and symbolic links that escape.
+///
+/// # Arguments
+///
+/// * `entries` - The entries about to be extracted.
+///
+/// # Returns
+///
+/// * `Vec<BlockedEntry>` - The unsafe entries, with the sanitized path each one would get.
+///
+/// 扫描会被写到输出目录之外的条目路径：绝对路径、驱动器号、`..` 组成部分以及越界的符号链接。
+///
+/// # 参数
+///
+/// * `entries` - 即将被解压的条目。
+///
+/// # 返回值
+///
+/// * `Vec<BlockedEntry>` - 不安全的条目，以及每个条目将获得的清理后路径。
+pub fn find_unsafe_entries(entries: &[&FileItem]) -> Vec<BlockedEntry> {
+    let symlinks: HashSet<&str> = entries
+        .iter()
+        .filter(|item| item.symlink_target.is_some())
+        .map(|item| item.name.trim_end_matches('/'))
+        .collect();
+
+    let mut blocked = Vec::new();
+    for item in entries {
+        let name = item.name.replace('\\', "/");
+        let reason = if name.starts_with('/') {
+            Some(UnsafePathReason::AbsolutePath)
+        } else if has_drive_letter(&name) {
+            Some(UnsafePathReason::DriveLetter)
+        } else if name.split('/').any(|component| component == "..") {
+            Some(UnsafePathReason::ParentTraversal)
+        } else if item.symlink_target.as_deref().is_some_and(|target| symlink_escapes(&name, target)) {
+            Some(UnsafePathReason::SymlinkEscape)
+        } else if ancestors(&name).any(|ancestor| symlinks.contains(ancestor)) {
+            Some(UnsafePathReason::ThroughSymlink)
+        } else {
+            None
+        };
+
+        if let Some(reason) = reason {
+            // A link is never rewritten: its target would still point wherever it points
+            // 链接永远不会被改写：其目标仍会指向原来的位置
+            let sanitized_path = match reason {
+                UnsafePathReason::SymlinkEscape | UnsafePathReason::ThroughSymlink => None,
+                _ if item.symlink_target.is_some() => None,
+                _ => sanitize_path(&name),
+            };
+            blocked.push(BlockedEntry { path: item.name.clone(), reason, sanitized_path });
+        }
+    }
+    blocked
+}
+
+/// Checks the entries about to be extracted against the limits, using the sizes from the listing.
+///
+/// # Arguments
+///
+/// * `entries` - The entries about to be extracted.
+/// * `limits` - The limits to enforce.
+///
+/// # Returns
+///
+/// * `Ok(())` - If no limit is exceeded.
+/// * `Err(SoarZipError::LimitExceeded)` - Naming the first limit that is exceeded.
+///
+/// 使用列表中的大小，根据限制检查即将被解压的条目。
+///
+/// # 参数
+///
+/// * `entries` - 即将被解压的条目。
+/// * `limits` - 要执行的限制。
+///
+/// # 返回值
+///
+/// * `Ok(())` - 如果没有超出任何限制。
+/// * `Err(SoarZipError::LimitExceeded)` - 指出第一个被超出的限制。
+pub fn check_extraction_limits(entries: &[&FileItem], limits: &ExtractionLimits) -> Result<(), SoarZipError> {
+    if let Some(max_entries) = limits.max_entries {
+        if entries.len() > max_entries {
+            return Err(SoarZipError::LimitExceeded(format!(
+                "The archive contains {} entries, more than the limit of {}.",
+                entries.len(),
+                max_entries
+            )));
+        }
+    }
+
+    if let Some(max_total_size) = limits.max_total_size {
+        let total_size = entries.iter().filter(|item| !item.is_dir).fold(0u64, |sum, item| sum.saturating_add(item.size));
+        if total_size > max_total_size {
+            return Err(SoarZipError::LimitExceeded(format!(
+                "The entries would unpack to {} bytes, more than the limit of {} bytes.",
+                total_size, max_total_size
+            )));
+        }
+    }
+
+    for item in entries {
+        if let Some(max_path_depth) = limits.max_path_depth {
+            let depth = item.name.trim_end_matches('/').split('/').filter(|component| !component.is_empty()).count();
+            if depth > max_path_depth {
+                return Err(SoarZipError::LimitExceeded(format!(
+                    "Entry '{}' is nested {} folders deep, more than the limit of {}.",
+                    item.name, depth, max_path_depth
+                )));
+            }
+        }
+
+        // In solid archives only the first entry of a block carries the packed size, so this
+        // underestimates the ratio there; the output watchdog covers what slips through
+        // 在固实压缩包中只有块的第一个条目带有压缩后大小，因此这里会低估压缩比；漏网之鱼由输出监视器处理
+        if let (Some(max_ratio), Some(packed_size)) = (limits.max_compression_ratio, item.packed_size) {
+            if item.is_dir || packed_size == 0 || item.size < RATIO_CHECK_MIN_SIZE {
+                continue;
+            }
+            let ratio = item.size as f64 / packed_size as f64;
+            if ratio > max_ratio {
+                return Err(SoarZipError::LimitExceeded(format!(
+                    "Entry '{}' would unpack {} bytes from {} bytes (ratio {:.0}:1), more than the limit of {:.0}:1.",
+                    item.name, item.size, packed_size, ratio, max_ratio
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Decides where each selected entry is written, relative to the output directory.
+///
+/// Skipped unsafe entries are left out and sanitized ones use their sanitized path. With
+/// [`PathMode::Relative`], `base_path` is removed from the front of every path; with
+/// [`PathMode::Flatten`], folders are left out and files keep only their name, where later
+/// files with an already used name (compared case-insensitively) become `name_N.ext`.
+///
+/// # Arguments
+///
+/// * `entries` - The entries about to be extracted.
+/// * `blocked_entries` - The unsafe entries among them, as found by [`find_unsafe_entries`].
+/// * `path_mode` - How stored paths are reproduced.
+/// * `base_path` - The folder inside the archive that relative paths start from.
+///
+/// # Returns
+///
+/// * `Ok(Vec<PlannedEntry>)` - The entries to write and their targets.
+/// * `Err(SoarZipError::InvalidInput)` - If an entry outside `base_path` is to be extracted relative to it.
+///
+/// 决定每个所选条目相对于输出目录的写入位置。
+///
+/// 被跳过的不安全条目会被排除，被清理的条目使用其清理后的路径。使用 [`PathMode::Relative`] 时，
+/// 会从每个路径的开头去掉 `base_path`；使用 [`PathMode::Flatten`] 时，文件夹会被排除，文件只保留其名称，
+/// 名称已被使用（不区分大小写比较）的后续文件会变为 `name_N.ext`。
+///
+/// # 参数
+///
+/// * `entries` - 即将被解压的条目。
+/// * `blocked_entries` - 其中不安全的条目，由 [`find_unsafe_entries`] 找出。
+/// * `path_mode` - 如何重现存储路径。
+/// * `base_path` - 相对路径起始的压缩包内文件夹。
+///
+/// # 返回值
+///
+/// * `Ok(Vec<PlannedEntry>)` - 要写入的条目及其目标位置。
+/// * `Err(SoarZipError::InvalidInput)` - 如果要相对于 `base_path` 解压位于其之外的条目。
+pub fn plan_entries<'a>(
+    entries: &[&'a FileItem],
+    blocked_entries: &[BlockedEntry],
+    path_mode: PathMode,
+    base_path: &str,
+) -> Result<Vec<PlannedEntry<'a>>, SoarZipError> {
+    let base = normalize_entry_path(base_path);
+    let mut planned = Vec::new();
+    for &item in entries {
+        let (stored, sanitized) = match blocked_entries.iter().find(|entry| entry.path == item.name) {
+            Some(entry) => match &entry.sanitized_path {
+                Some(path) => (normalize_entry_path(path), true),
+                None => continue,
+            },
+            None => (normalize_entry_path(&item.name), false),
+        };
+        let target = match path_mode {
+            PathMode::Full => stored,
+            PathMode::Relative if base.is_empty() => stored,
+            PathMode::Relative => match stored.strip_prefix(base.as_str()).and_then(|rest| rest.strip_prefix('/')) {
+                Some(rest) => rest.to_string(),
+                // The base folder itself already exists as the output directory / 基准文件夹本身即输出目录
+                None if stored == base => continue,
+                // A sanitized path no longer says where the entry came from / 清理后的路径已无法表明条目的来源
+                None if sanitized => stored,
+                None => {
+                    return Err(SoarZipError::InvalidInput(format!(
+                        "'{}' is not inside '{}' and cannot be extracted relative to it.",
+                        item.name, base_path
+                    )))
+                }
+            },
+            PathMode::Flatten if item.is_dir => continue,
+            PathMode::Flatten => stored.rsplit('/').next().unwrap_or_default().to_string(),
+        };
+        if target.is_empty() {
+            continue;
+        }
+        planned.push(PlannedEntry { item, target, individual: sanitized });
+    }
+
+    if path_mode == PathMode::Flatten {
+        // Names are compared case-insensitively, since Windows and macOS file systems do
+        // 名称不区分大小写比较，因为 Windows 和 macOS 的文件系统也是如此
+        let all_names: HashSet<String> = planned.iter().map(|entry| entry.target.to_lowercase()).collect();
+        let mut used = HashSet::new();
+        for entry in &mut planned {
+            if used.insert(entry.target.to_lowercase()) {
+                continue;
+            }
+            let name = Path::new(&entry.target);
+            let stem = name.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+            let extension = name.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+            let renamed = (1u32..)
+                .map(|n| format!("{}_{}{}", stem, n, extension))
+                .find(|candidate| !all_names.contains(&candidate.to_lowercase()) && !used.contains(&candidate.to_lowercase()))
+                .expect("an unused file name exists");
+            used.insert(renamed.to_lowercase());
+            entry.target = renamed;
+            entry.individual = true;
+        }
+    }
+    Ok(planned)
+}
+
+/// Chooses the directory for a [`ExtractLayout::Smart`] extraction.
+///
+/// A single top-level entry (e.g., `foo/...` or one file) is extracted directly, which avoids
+/// `foo/foo/...`. Several top-level entries go into a subfolder named after the archive, so they
+/// do not scatter across `output_path`.
+///
+/// # Arguments
+///
+/// * `output_path` - The folder the user chose to extract "here".
+/// * `archive_path` - The archive, whose name becomes the subfolder name.
+/// * `planned` - The entries about to be written, as planned by [`plan_entries`].
+///
+/// 为 [`ExtractLayout::Smart`] 解压选择目录。
+///
+/// 单个顶层条目（例如 `foo/...` 或单个文件）直接解压，以避免出现 `foo/foo/...`。
+/// 多个顶层条目会放入以压缩包命名的子文件夹中，以免散落在 `output_path` 中。
+///
+/// # 参数
+///
+/// * `output_path` - 用户选择"解压到此处"的文件夹。
+/// * `archive_path` - 压缩包，其名称将作为子文件夹名称。
+/// * `planned` - 即将被写入的条目，由 [`plan_entries`] 规划。
+pub fn smart_output_directory(output_path: &Path, archive_path: &Path, planned: &[PlannedEntry]) -> PathBuf {
+    if top_level_names(planned).len() <= 1 {
+        return output_path.to_path_buf();
+    }
+    let subfolder = output_path.join(archive_folder_name(archive_path));
+    match std::fs::metadata(&subfolder) {
+        // An existing folder of that name is reused; a file of that name is not
+        // 已存在的同名文件夹会被复用；同名文件则不会
+        Ok(metadata) if !metadata.is_dir() => unique_path(&subfolder),
+        _ => subfolder,
+    }
+}
+
+/// The archive file name without its archive extensions: `backup.tar.gz` -> `backup`,
+/// `photos.7z.001` -> `photos`, `data.part1.rar` -> `data`.
+///
+/// 去掉压缩包扩展名后的压缩包文件名：`backup.tar.gz` -> `backup`、
+/// `photos.7z.001` -> `photos`、`data.part1.rar` -> `data`。
+pub fn archive_folder_name(archive_path: &Path) -> String {
+    const COMPOUND_EXTENSIONS: [&str; 5] = [".tar.gz", ".tar.bz2", ".tar.xz", ".tar.zst", ".tar.lz"];
+
+    let file_name = archive_path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    let mut name = file_name.as_str();
+
+    // Multi-volume suffixes first: "name.7z.001", "name.part1.rar" / 先处理分卷后缀
+    if let Some((stem, number)) = name.rsplit_once('.') {
+        if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) {
+            name = stem;
+        }
+    }
+    let compound = COMPOUND_EXTENSIONS.iter().find_map(|ext| {
+        let stem_len = name.len().checked_sub(ext.len())?;
+        name.get(stem_len..).filter(|suffix| suffix.eq_ignore_ascii_case(ext)).map(|_| stem_len)
+    });
+    if let Some(stem_len) = compound {
+        name = &name[..stem_len];
+    } else if let Some((stem, _)) = name.rsplit_once('.').filter(|(stem, _)| !stem.is_empty()) {
+        name = stem;
+        if let Some((stem, part)) = name.rsplit_once('.') {
+            let is_volume = part.len() > 4
+                && part.get(..4).is_some_and(|prefix| prefix.eq_ignore_ascii_case("part"))
+                && part[4..].chars().all(|c| c.is_ascii_digit());
+            if is_volume {
+                name = stem;
+            }
+        }
+    }
+
+    if name.is_empty() {
+        file_name
+    } else {
+        name.to_string()
+    }
+}
+
+/// Finds the entries that would replace something already in the output directory.
+///
+/// # Arguments
+///
+/// * `output_path` - The output directory.
+/// * `planned` - The entries about to be written, as planned by [`plan_entries`].
+///
+/// # Returns
+///
+/// * `Vec<String>` - The paths (inside the archive) of the conflicting entries.
+///
+/// 查找会替换输出目录中已有内容的条目。
+///
+/// # 参数
+///
+/// * `output_path` - 输出目录。
+/// * `planned` - 即将被写入的条目，由 [`plan_entries`] 规划。
+///
+/// # 返回值
+///
+/// * `Vec<String>` - 冲突条目（在压缩包内）的路径。
+pub fn find_conflicts(output_path: &Path, planned: &[PlannedEntry]) -> Vec<String> {
+    planned
+        .iter()
+        .filter_map(|entry| {
+            let metadata = std::fs::symlink_metadata(output_path.join(&entry.target)).ok()?;
+            // Extracting a folder into an existing folder merges them, which is not a conflict
+            // 将文件夹解压到已存在的文件夹中会合并二者，这不算冲突
+            (!(entry.item.is_dir && metadata.is_dir())).then(|| entry.item.name.clone())
+        })
+        .collect()
+}
+
+/// Returns `path` if nothing exists there, otherwise the first free `name_N.ext` next to it,
+/// following 7-Zip's own naming for `-aou`.
+///
+/// 如果 `path` 处不存在任何内容则返回 `path`，否则返回其旁边第一个空闲的 `name_N.ext`，
+/// 与 7-Zip 的 `-aou` 命名方式一致。
+pub fn unique_path(path: &Path) -> PathBuf {
+    if std::fs::symlink_metadata(path).is_err() {
+        return path.to_path_buf();
+    }
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    let extension = path.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+    (1u32..)
+        .map(|n| path.with_file_name(format!("{}_{}{}", stem, n, extension)))
+        .find(|candidate| std::fs::symlink_metadata(candidate).is_err())
+        .expect("an unused file name exists")
+}
+
+/// Applies an overwrite policy to a file about to be written outside of 7-Zip.
+///
+/// # Returns
+///
+/// * `Ok(Some(path))` - Where to write the file.
+/// * `Ok(None)` - If the file should not be written (it exists and is kept).
+/// * `Err(SoarZipError)` - If the existing file could not be renamed.
+///
+/// 对即将在 7-Zip 之外写入的文件应用覆盖策略。
+///
+/// # 返回值
+///
+/// * `Ok(Some(path))` - 文件的写入位置。
+/// * `Ok(None)` - 如果不应写入该文件（文件已存在且被保留）。
+/// * `Err(SoarZipError)` - 如果无法重命名已存在的文件。
+pub fn resolve_destination(destination: PathBuf, policy: OverwritePolicy) -> Result<Option<PathBuf>, SoarZipError> {
+    if std::fs::symlink_metadata(&destination).is_err() {
+        return Ok(Some(destination));
+    }
+    match policy {
+        OverwritePolicy::OverwriteAll => Ok(Some(destination)),
+        OverwritePolicy::SkipExisting | OverwritePolicy::Ask => Ok(None),
+        OverwritePolicy::RenameExtracted => Ok(Some(unique_path(&destination))),
+        OverwritePolicy::RenameExisting => {
+            let renamed = unique_path(&destination);
+            std::fs::rename(&destination, &renamed).map_err(|e| {
+                SoarZipError::Io(format!("Failed to rename '{}' to '{}': {}", destination.display(), renamed.display(), e))
+            })?;
+            Ok(Some(destination))
+        }
+    }
+}
+
+/// A hidden folder inside the output directory that 7-Zip extracts into before the entries are
+/// moved to their final place. Being on the same disk, moving them is a cheap rename.
+/// The folder is removed with anything left in it when dropped.
+///
+/// 输出目录内的一个隐藏文件夹，7-Zip 先解压到其中，然后再将条目移动到最终位置。
+/// 由于位于同一磁盘上，移动只是一次低开销的重命名。被丢弃时，该文件夹及其中剩余的内容会被删除。
+pub struct StagingDirectory {
+    path: PathBuf,
+}
+
+impl StagingDirectory {
+    /// Creates a new staging folder inside `output_path`.
+    ///
+    /// 在 `output_path` 内创建新的暂存文件夹。
+    pub fn create(output_path: &Path) -> Result<Self, SoarZipError> {
+        let path = unique_path(&output_path.join(".soarzip-staging"));
+        std::fs::create_dir(&path).map_err(|e| {
+            SoarZipError::Io(format!("Failed to create staging directory '{}': {}", path.display(), e))
+        })?;
+        Ok(StagingDirectory { path })
+    }
+
+    /// The path of the staging folder / 暂存文件夹的路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The name of the staging folder inside the output directory / 暂存文件夹在输出目录中的名称
+    pub fn name(&self) -> String {
+        self.path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+    }
+}
+
+impl Drop for StagingDirectory {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.path) {
+            log_error(&format!("Failed to remove staging directory '{}': {}", self.path.display(), e));
+        }
+    }
+}
+
+/// Moves the contents of `source` into `destination`, merging folders that exist in both and
+/// applying the overwrite policy to files. A file never replaces an existing folder.
+///
+/// # Arguments
+///
+/// * `source` - The folder whose contents are moved.
+/// * `destination` - The folder they are moved into; created if needed.
+/// * `policy` - What to do with files that already exist in `destination`.
+///
+/// 将 `source` 的内容移动到 `destination` 中，合并两边都存在的文件夹，并对文件应用覆盖策略。
+/// 文件永远不会替换已存在的文件夹。
+///
+/// # 参数
+///
+/// * `source` - 其内容将被移动的文件夹。
+/// * `destination` - 内容被移入的文件夹；如有需要会被创建。
+/// * `policy` - 如何处理 `destination` 中已存在的文件。
+pub fn merge_directory(source: &Path, destination: &Path, policy: OverwritePolicy) -> Result<(), SoarZipError> {
+    std::fs::create_dir_all(destination).map_err(|e| {
+        SoarZipError::Io(format!("Failed to create directory '{}': {}", destination.display(), e))
+    })?;
+    let children = std::fs::read_dir(source).map_err(|e| {
+        SoarZipError::Io(format!("Failed to read directory '{}': {}", source.display(), e))
+    })?;
+    for child in children.flatten() {
+        let from = child.path();
+        let to = destination.join(child.file_name());
+        let is_dir = child.file_type().map(|file_type| file_type.is_dir()).unwrap_or(false);
+        let existing = std::fs::symlink_metadata(&to).ok();
+        if is_dir && existing.as_ref().is_some_and(|metadata| metadata.is_dir()) {
+            merge_directory(&from, &to, policy)?;
+            continue;
+        }
+        let Some(to) = resolve_destination(to, policy)? else { continue };
+        // Only `OverwriteAll` leaves something in the way / 只有 `OverwriteAll` 会留下占位的内容
+        if let Ok(metadata) = std::fs::symlink_metadata(&to) {
+            if metadata.is_dir() {
+                return Err(SoarZipError::Io(format!("Cannot replace the folder '{}' with a file.", to.display())));
+            }
+            std::fs::remove_file(&to).map_err(|e| {
+                SoarZipError::Io(format!("Failed to replace '{}': {}", to.display(), e))
+            })?;
+        }
+        std::fs::rename(&from, &to).map_err(|e| {
+            SoarZipError::Io(format!("Failed to move '{}' to '{}': {}", from.display(), to.display(), e))
+        })?;
+    }
+    Ok(())
+}
+
+/// Fails early if the destination file system cannot hold the unpacked entries plus a safety margin.
+/// If the free space cannot be determined, the check is skipped rather than blocking the extraction.
+///
+/// # Arguments
+///
+/// * `output_path` - The output directory, or a path below an existing directory on the same disk.
+/// * `required_bytes` - The total unpacked size of the entries to extract.
+/// * `margin` - Extra bytes that must remain free afterwards.
+///
+/// # Returns
+///
+/// * `Ok(())` - If there is enough room, or the free space is unknown.
+/// * `Err(SoarZipError::DiskFull)` - If there is not enough room.
+///
+/// 如果目标文件系统无法容纳解压后的条目以及安全余量，则提前失败。
+/// 如果无法确定可用空间，则跳过检查，而不是阻止解压。
+///
+/// # 参数
+///
+/// * `output_path` - 输出目录，或同一磁盘上某个现有目录之下的路径。
+/// * `required_bytes` - 要解压的条目的未压缩总大小。
+/// * `margin` - 解压后必须保留的额外空闲字节数。
+///
+/// # 返回值
+///
+/// * `Ok(())` - 如果空间足够，或可用空间未知。
+/// * `Err(SoarZipError::DiskFull)` - 如果空间不足。
+pub fn check_disk_space(output_path: &Path, required_bytes: u64, margin: u64) -> Result<(), SoarZipError> {
+    // The output directory may not exist yet; its nearest existing ancestor is on the same disk
+    // 输出目录可能尚不存在；其最近的现有祖先目录位于同一磁盘上
+    let Some(existing) = output_path.ancestors().find(|path| path.exists()) else {
+        return Ok(());
+    };
+    let Some(available) = available_space(existing) else {
+        log_error(&format!("Could not determine the free space at '{}', skipping the disk space check", existing.display()));
+        return Ok(());
+    };
+    let needed = required_bytes.saturating_add(margin);
+    log_info(&format!("Disk space check at '{}': {} bytes needed, {} bytes available", existing.display(), needed, available));
+    if needed > available {
+        return Err(SoarZipError::DiskFull(format!(
+            "Not enough free space at '{}': the extraction needs {} bytes (including a margin of {} bytes), but only {} bytes are available.",
+            existing.display(), needed, margin, available
+        )));
+    }
+    Ok(())
+}
+
+/// The number of bytes available to the current user on the file system containing `path`.
+/// 包含 `path` 的文件系统上当前用户可用的字节数。
+#[cfg(unix)]
+fn available_space(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stats) } != 0 {
+        return None;
+    }
+    #[allow(clippy::unnecessary_cast)] // The field types differ between platforms / 字段类型因平台而异
+    Some((stats.f_bavail as u64).saturating_mul(stats.f_frsize as u64))
+}
+
+/// The number of bytes available to the current user on the file system containing `path`.
+/// 包含 `path` 的文件系统上当前用户可用的字节数。
+#[cfg(windows)]
+fn available_space(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut available: u64 = 0;
+    let ok = unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, std::ptr::null_mut(), std::ptr::null_mut()) };
+    (ok != 0).then_some(available)
+}
+
+/// Free space cannot be queried on this platform / 此平台上无法查询可用空间
+#[cfg(not(any(unix, windows)))]
+fn available_space(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Measures the data actually written during an extraction and aborts the job once it exceeds
+/// the size limit. The sizes in archive headers can be forged, so the listing alone is not enough.
+///
+/// Only the folders the extracted files are written to are measured, without descending into
+/// subfolders, and files that are unchanged since the start do not count, so content that was
+/// already in the output directory is ignored. Files 7-Zip writes under a new name next to an
+/// existing one are still counted. The measurement stops when the watchdog is dropped.
+///
+/// 测量解压期间实际写入的数据，一旦超出大小限制便中止作业。压缩包头部中的大小可以被伪造，
+/// 因此仅靠列表是不够的。
+///
+/// 只测量被解压文件写入的文件夹（不进入子文件夹），且自开始以来未改变的文件不计入，因此输出目录中
+/// 原有的内容会被忽略。7-Zip 在已有文件旁以新名称写入的文件仍会被计入。监视器被丢弃时测量停止。
+pub struct OutputSizeWatchdog {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl OutputSizeWatchdog {
+    /// Starts measuring the files written for `targets` (file paths relative to `output_path`) on a background thread.
+    ///
+    /// 在后台线程中开始测量为 `targets`（相对于 `output_path` 的文件路径）写入的文件。
+    pub fn start(output_path: &Path, targets: &[String], max_total_size: u64, job: JobHandle) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let folders: HashSet<PathBuf> = targets
+            .iter()
+            .filter_map(|target| output_path.join(target).parent().map(Path::to_path_buf))
+            .collect();
+        // What was there before is recorded so that only new or changed files are measured
+        // 记录原有的内容，以便只测量新增或已更改的文件
+        let baseline: HashMap<PathBuf, FileStamp> = folders.iter().flat_map(|folder| folder_files(folder)).collect();
+        let thread_stop = Arc::clone(&stop);
+        let thread = std::thread::spawn(move || {
+            while !thread_stop.load(Ordering::SeqCst) && !job.is_cancelled() {
+                let written = folders
+                    .iter()
+                    .flat_map(|folder| folder_files(folder))
+                    .filter(|(path, stamp)| baseline.get(path) != Some(stamp))
+                    .fold(0u64, |sum, (_, stamp)| sum.saturating_add(stamp.len));
+                if written > max_total_size {
+                    job.abort(SoarZipError::LimitExceeded(format!(
+                        "Extraction was stopped after writing {} bytes, more than the limit of {} bytes.",
+                        written, max_total_size
+                    )));
+                    break;
+                }
+                std::thread::sleep(WATCHDOG_INTERVAL);
+            }
+        });
+        OutputSizeWatchdog { stop, thread: Some(thread) }
+    }
+}
+
+impl Drop for OutputSizeWatchdog {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// The size and modification time of a file, used to tell whether it has been written to.
+/// 文件的大小和修改时间，用于判断它是否被写入过。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+/// The files directly inside `folder`, with their stamps. Symbolic links are not followed.
+/// `folder` 中直接包含的文件及其状态。不跟随符号链接。
+fn folder_files(folder: &Path) -> Vec<(PathBuf, FileStamp)> {
+    let Ok(children) = std::fs::read_dir(folder) else { return Vec::new() };
+    children
+        .flatten()
+        .filter_map(|child| {
+            let metadata = std::fs::symlink_metadata(child.path()).ok()?;
+            if metadata.is_dir() {
+                return None;
+            }
+            Some((child.path(), FileStamp { len: metadata.len(), modified: metadata.modified().ok() }))
+        })
+        .collect()
+}
+
+/// The distinct top-level names the planned entries are written under, e.g. `docs` for `docs/a.txt`.
+/// 规划条目写入位置中互不相同的顶层名称，例如 `docs/a.txt` 对应 `docs`。
+pub fn top_level_names(planned: &[PlannedEntry]) -> Vec<String> {
+    let mut names: Vec<String> = planned
+        .iter()
+        .filter_map(|entry| entry.target.split('/').find(|component| !component.is_empty()).map(str::to_string))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Extracts a single entry to a file by streaming it through `7z e -so`.
+/// Used for entries whose stored path cannot be handed to `7z x`.
+///
+/// # Arguments
+///
+/// * `seven_zip_path` - The path to the 7-Zip executable.
+/// * `archive_path` - The archive containing the entry.
+/// * `entry_name` - The path of the entry inside the archive.
+/// * `password` - Optional password for encrypted archives.
+/// * `destination` - The file to write. Its parent directories are created if needed.
+///
+/// 通过 `7z e -so` 流式输出，将单个条目解压到文件。
+/// 用于存储路径无法交给 `7z x` 的条目。
+///
+/// # 参数
+///
+/// * `seven_zip_path` - 7-Zip 可执行文件的路径。
+/// * `archive_path` - 包含该条目的压缩包。
+/// * `entry_name` - 条目在压缩包内的路径。
+/// * `password` - 用于加密压缩包的可选密码。
+/// * `destination` - 要写入的文件。如有需要会创建其父目录。
+pub fn extract_entry_to_file(
+    seven_zip_path: &Path,
+    archive_path: &str,
+    entry_name: &str,
+    password: Option<&str>,
+    destination: &Path,
+) -> Result<(), SoarZipError> {
+    if let Some(parent) = destination.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            SoarZipError::Io(format!("Failed to create directory '{}': {}", parent.display(), e))
+        })?;
+    }
+    let file = File::create(destination).map_err(|e| {
+        SoarZipError::Io(format!("Failed to create file '{}': {}", destination.display(), e))
+    })?;
+
+    // '-spd' takes the name literally, so '*' or '?' in it never selects other entries
+    // '-spd' 按字面处理名称，因此其中的 '*' 或 '?' 永远不会选中其他条目
+    let mut args = vec!["e".to_string(), "-so".to_string(), "-spd".to_string()];
+    args.extend(password_switch(password));
+    args.push(archive_path.to_string());
+    args.push("--".to_string());
+    args.push(entry_name.to_string());
+
+    let output = new_7z_command(seven_zip_path, &args)
+        .stdout(Stdio::from(file))
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(spawn_error)?;
+    if !output.status.success() {
+        let _ = std::fs::remove_file(destination);
+        let error = SoarZipError::from_7z_output(&output, "extract entry");
+        log_error(&format!("Failed to extract '{}' to '{}': {}", entry_name, destination.display(), error));
+        return Err(error);
+    }
+    log_info(&format!("Extracted '{}' to '{}'", entry_name, destination.display()));
+    Ok(())
+}
+
+/// An entry path with forward slashes and without leading or trailing separators.
+/// 使用正斜杠且没有前导或尾随分隔符的条目路径。
+fn normalize_entry_path(path: &str) -> String {
+    path.replace('\\', "/").trim_matches('/').to_string()
+}
+
+/// Whether a path starts with a Windows drive letter, e.g. `C:` or `c:/`.
+/// 路径是否以 Windows 驱动器号开头，例如 `C:` 或 `c:/`。
+fn has_drive_letter(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+/// The proper ancestors of an archive path, e.g. `a/b/c` -> `a/b`, `a`.
+/// 压缩包路径的真祖先路径，例如 `a/b/c` -> `a/b`、`a`。
+fn ancestors(path: &str) -> impl Iterator<Item = &str> {
+    let path = path.trim_end_matches('/');
+    path.match_indices('/').map(move |(index, _)| &path[..index])
+}
+
+/// Whether a symbolic link stored at `link_path` would point outside the output directory.
+/// The target is resolved lexically, relative to the folder containing the link.
+///
+/// 存储在 `link_path` 的符号链接是否会指向输出目录之外。
+/// 目标按词法解析，相对于包含该链接的文件夹。
+fn symlink_escapes(link_path: &str, target: &str) -> bool {
+    let target = target.replace('\\', "/");
+    if target.starts_with('/') || has_drive_letter(&target) {
+        return true;
+    }
+    let mut depth: Vec<&str> = link_path.trim_end_matches('/').split('/').collect();
+    depth.pop(); // The link itself / 链接本身
+    for component in target.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                if depth.pop().is_none() {
+                    return true;
+                }
+            }
+            other => depth.push(other),
+        }
+    }
+    false
+}
+
+/// Turns an unsafe path into a relative one: drops the drive letter, the root and every
+/// `.`/`..` component. Returns `None` if nothing is left.
+///
+/// 将不安全的路径转换为相对路径：去掉驱动器号、根目录以及所有 `.`/`..` 组成部分。
+/// 如果没有剩余内容则返回 `None`。
+fn sanitize_path(path: &str) -> Option<String> {
+    let path = if has_drive_letter(path) { &path[2..] } else { path };
+    let components: Vec<&str> = path
+        .split('/')
+        .filter(|component| !matches!(*component, "" | "." | ".."))
+        .collect();
+    if components.is_empty() {
+        None
+    } else {
+        Some(components.join("/"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str) -> FileItem {
+        FileItem { name: name.to_string(), is_dir: name.ends_with('/'), ..Default::default() }
+    }
+
+    fn link(name: &str, target: &str) -> FileItem {
+        FileItem { symlink_target: Some(target.to_string()), ..file(name) }
+    }
+
+    fn blocked(items: &[FileItem]) -> Vec<(String, UnsafePathReason, Option<String>)> {
+        let entries: Vec<&FileItem> = items.iter().collect();
+        find_unsafe_entries(&entries)
+            .into_iter()
+            .map(|entry| (entry.path, entry.reason, entry.sanitized_path))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_safe_paths() {
+        let items = [file("docs/"), file("docs/a.txt"), file("docs/a..b.txt"), file("./notes.md"), link("docs/up", "..")];
+        assert!(blocked(&items).is_empty());
+    }
+
+    #[test]
+    fn finds_absolute_paths_and_drive_letters() {
+        let items = [file("/etc/passwd"), file("C:\\Windows\\evil.dll"), file("d:relative.txt")];
+        assert_eq!(
+            blocked(&items),
+            vec![
+                ("/etc/passwd".to_string(), UnsafePathReason::AbsolutePath, Some("etc/passwd".to_string())),
+                ("C:\\Windows\\evil.dll".to_string(), UnsafePathReason::DriveLetter, Some("Windows/evil.dll".to_string())),
+                ("d:relative.txt".to_string(), UnsafePathReason::DriveLetter, Some("relative.txt".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_parent_traversal() {
+        let items = [file("../../outside.txt"), file("docs\\..\\..\\x.txt"), file("..")];
+        assert_eq!(
+            blocked(&items),
+            vec![
+                ("../../outside.txt".to_string(), UnsafePathReason::ParentTraversal, Some("outside.txt".to_string())),
+                ("docs\\..\\..\\x.txt".to_string(), UnsafePathReason::ParentTraversal, Some("docs/x.txt".to_string())),
+                ("..".to_string(), UnsafePathReason::ParentTraversal, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn never_rewrites_links() {
+        let items = [
+            link("docs/escape", "../../etc"),
+            link("abs", "/etc/shadow"),
+            link("win", "C:\\Windows"),
+            link("/abs-link", "target"),
+        ];
+        assert_eq!(
+            blocked(&items),
+            vec![
+                ("docs/escape".to_string(), UnsafePathReason::SymlinkEscape, None),
+                ("abs".to_string(), UnsafePathReason::SymlinkEscape, None),
+                ("win".to_string(), UnsafePathReason::SymlinkEscape, None),
+                ("/abs-link".to_string(), UnsafePathReason::AbsolutePath, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_paths_through_stored_links() {
+        let items = [link("shared", "docs"), file("shared/inside.txt"), file("shared/deeper/x.txt"), file("sharedfile.txt")];
+        assert_eq!(
+            blocked(&items),
+            vec![
+                ("shared/inside.txt".to_string(), UnsafePathReason::ThroughSymlink, None),
+                ("shared/deeper/x.txt".to_string(), UnsafePathReason::ThroughSymlink, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn sanitizes_paths() {
+        assert_eq!(sanitize_path("/a/./b/../c"), Some("a/b/c".to_string()));
+        assert_eq!(sanitize_path("C:/x/y"), Some("x/y".to_string()));
+        assert_eq!(sanitize_path("//server/share/file"), Some("server/share/file".to_string()));
+        assert_eq!(sanitize_path("../.."), None);
+        assert_eq!(sanitize_path("/"), None);
+    }
+
+    fn plan(items: &[FileItem], path_mode: PathMode, base_path: &str) -> Result<Vec<(String, bool)>, SoarZipError> {
+        let entries: Vec<&FileItem> = items.iter().collect();
+        let blocked = find_unsafe_entries(&entries);
+        Ok(plan_entries(&entries, &blocked, path_mode, base_path)?
+            .into_iter()
+            .map(|entry| (entry.target, entry.individual))
+            .collect())
+    }
+
+    #[test]
+    fn flatten_renames_duplicate_names() {
+        let items = [file("a/"), file("a/x.txt"), file("b/X.txt"), file("c/x.txt"), file("c/x_1.txt"), file("d/README")];
+        assert_eq!(
+            plan(&items, PathMode::Flatten, "").unwrap(),
+            vec![
+                ("x.txt".to_string(), false),
+                ("X_2.txt".to_string(), true),
+                ("x_3.txt".to_string(), true),
+                ("x_1.txt".to_string(), false),
+                ("README".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn flatten_renames_files_without_extension() {
+        let items = [file("a/Makefile"), file("b/makefile")];
+        assert_eq!(
+            plan(&items, PathMode::Flatten, "").unwrap(),
+            vec![("Makefile".to_string(), false), ("makefile_1".to_string(), true)]
+        );
+    }
+
+    #[test]
+    fn relative_strips_the_base_folder() {
+        let items = [file("docs/"), file("docs/img/"), file("docs/img/a.png"), file("docs/b.txt")];
+        assert_eq!(
+            plan(&items, PathMode::Relative, "docs/").unwrap(),
+            vec![("img".to_string(), false), ("img/a.png".to_string(), false), ("b.txt".to_string(), false)]
+        );
+        assert_eq!(plan(&items, PathMode::Relative, "").unwrap().len(), items.len());
+    }
+
+    #[test]
+    fn relative_rejects_entries_outside_the_base_folder() {
+        let items = [file("docs/a.txt"), file("docsx/b.txt")];
+        assert!(matches!(plan(&items, PathMode::Relative, "docs"), Err(SoarZipError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn skips_blocked_entries_that_cannot_be_rewritten() {
+        let items = [link("escape", "../../etc"), file("../up.txt"), file("/abs/b.txt"), file("ok.txt")];
+        assert_eq!(
+            plan(&items, PathMode::Full, "").unwrap(),
+            vec![("up.txt".to_string(), true), ("abs/b.txt".to_string(), true), ("ok.txt".to_string(), false)]
+        );
+    }
+}
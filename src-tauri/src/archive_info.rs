@@ -0,0 +1,207 @@
+//! Archive-level properties parsed from the header block of `7z l -slt`.
+//! 从 `7z l -slt` 的头部块解析出的压缩包级别属性。
+
+use serde::Serialize;
+
+use super::file_item::FileItem;
+
+/// Properties of an archive as a whole, shown in the properties panel.
+/// 压缩包整体的属性，显示在属性面板中。
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ArchiveInfo {
+    /// The format 7-Zip detected (e.g., "7z", "zip", "gzip").
+    pub archive_type: String,
+    /// The size of the archive on disk in bytes.
+    pub physical_size: Option<u64>,
+    /// The size of the archive headers in bytes, if reported.
+    pub headers_size: Option<u64>,
+    /// The compression method(s) used by the archive (e.g., "LZMA2:24 BCJ").
+    pub method: Option<String>,
+    /// Whether the archive is solid.
+    pub solid: bool,
+    /// The number of solid blocks, if reported.
+    pub blocks: Option<u64>,
+    /// The number of volumes of a multi-volume archive.
+    pub volumes: Option<u64>,
+    /// The archive comment, if any.
+    pub comment: Option<String>,
+    /// Physical size divided by the total unpacked size (e.g., 0.35 means 35%).
+    pub compression_ratio: Option<f64>,
+    /// Warnings 7-Zip printed while opening the archive.
+    pub warnings: Vec<String>,
+}
+
+/// An opened archive: its properties and its entries.
+/// 已打开的压缩包：其属性和条目。
+#[derive(Debug, Serialize, Clone)]
+pub struct OpenedArchive {
+    /// Archive-level properties.
+    pub info: ArchiveInfo,
+    /// The items in the archive.
+    pub entries: Vec<FileItem>,
+}
+
+/// Parses the header block of `7z l -slt` output (everything before the `----------` line)
+/// and any warnings 7-Zip printed, then derives the compression ratio from the entries.
+///
+/// # Arguments
+///
+/// * `stdout_output` - The decoded stdout of `7z l -slt`.
+/// * `stderr_output` - The decoded stderr, which may carry open warnings.
+/// * `entries` - The parsed entries, used for the compression ratio.
+///
+/// 解析 `7z l -slt` 输出的头部块（`----------` 行之前的所有内容）以及 7-Zip 打印的警告，
+/// 然后根据条目计算压缩率。
+///
+/// # 参数
+///
+/// * `stdout_output` - `7z l -slt` 解码后的 stdout。
+/// * `stderr_output` - 解码后的 stderr，可能包含打开时的警告。
+/// * `entries` - 已解析的条目，用于计算压缩率。
+pub fn parse_archive_info(stdout_output: &str, stderr_output: &str, entries: &[FileItem]) -> ArchiveInfo {
+    let mut info = ArchiveInfo::default();
+    let header = stdout_output.split("\n----------").next().unwrap_or("");
+    let mut in_header = false;
+    let mut in_warnings = false;
+
+    for line in header.lines() {
+        let line = line.trim();
+        if line == "--" {
+            // The properties of the archive follow this marker / 压缩包属性位于此标记之后
+            in_header = true;
+            continue;
+        }
+        if line.starts_with("Open WARNING:") || line.starts_with("WARNING:") {
+            info.warnings.push(line.to_string());
+            continue;
+        }
+        if !in_header {
+            continue;
+        }
+        if line == "WARNINGS:" || line == "ERRORS:" {
+            in_warnings = true;
+            continue;
+        }
+        let Some((key, value)) = line.split_once(" = ").or_else(|| line.strip_suffix(" =").map(|key| (key, ""))) else {
+            // Lines without "key = value" inside a WARNINGS block are the warnings themselves
+            // WARNINGS 块中不带 "key = value" 的行就是警告本身
+            if in_warnings && !line.is_empty() {
+                info.warnings.push(line.to_string());
+            }
+            continue;
+        };
+        in_warnings = false;
+        let value = value.trim();
+        match key {
+            "Type" => info.archive_type = value.to_string(),
+            "Physical Size" => info.physical_size = value.parse().ok(),
+            "Headers Size" => info.headers_size = value.parse().ok(),
+            "Method" if !value.is_empty() => info.method = Some(value.to_string()),
+            "Solid" => info.solid = value == "+",
+            "Blocks" => info.blocks = value.parse().ok(),
+            "Volumes" => info.volumes = value.parse().ok(),
+            "Comment" if !value.is_empty() => info.comment = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    for line in stderr_output.lines() {
+        let line = line.trim();
+        if line.starts_with("Open WARNING:") || line.starts_with("WARNING:") {
+            info.warnings.push(line.to_string());
+        }
+    }
+
+    // The sizes come from the archive headers and may be forged to overflow / 大小来自压缩包头部，可能被伪造以造成溢出
+    let unpacked_size = entries.iter().filter(|item| !item.is_dir).fold(0u64, |sum, item| sum.saturating_add(item.size));
+    info.compression_ratio = match info.physical_size {
+        Some(physical_size) if unpacked_size > 0 => Some(physical_size as f64 / unpacked_size as f64),
+        _ => None,
+    };
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FileItem, parse_archive_info};
+
+    const LISTING: &str = "\
+7-Zip 23.01 (x64) : Copyright (c) 1999-2023 Igor Pavlov : 2023-06-20
+
+Scanning the drive for archives:
+1 file, 1000 bytes (1 KiB)
+
+Listing archive: sample.7z
+
+--
+Path = sample.7z
+Type = 7z
+Physical Size = 1000
+Headers Size = 200
+Method = LZMA2:24 BCJ
+Solid = +
+Blocks = 1
+Comment = 
+WARNINGS:
+There are data after the end of archive
+
+----------
+Path = a.txt
+Size = 3000
+Packed Size = 800
+Modified = 2024-01-02 03:04:05
+Folder = -
+
+";
+
+    fn file(name: &str, size: u64) -> FileItem {
+        FileItem { name: name.to_string(), size, ..Default::default() }
+    }
+
+    #[test]
+    fn parses_the_header_block() {
+        let info = parse_archive_info(LISTING, "", &[file("a.txt", 3000)]);
+        assert_eq!(info.archive_type, "7z");
+        assert_eq!(info.physical_size, Some(1000));
+        assert_eq!(info.headers_size, Some(200));
+        assert_eq!(info.method.as_deref(), Some("LZMA2:24 BCJ"));
+        assert!(info.solid);
+        assert_eq!(info.blocks, Some(1));
+        assert_eq!(info.comment, None);
+        assert_eq!(info.warnings, vec!["There are data after the end of archive".to_string()]);
+    }
+
+    #[test]
+    fn ignores_the_entry_properties() {
+        // "Size" and "Path" of the entries must not overwrite the archive's values
+        // 条目的 "Size" 和 "Path" 不得覆盖压缩包的值
+        let info = parse_archive_info(LISTING, "", &[]);
+        assert_eq!(info.physical_size, Some(1000));
+        assert_eq!(info.archive_type, "7z");
+    }
+
+    #[test]
+    fn collects_open_warnings_from_stderr() {
+        let stderr = "WARNING: sample.7z\nOpen WARNING: Cannot open the file as expected archive type\n";
+        let info = parse_archive_info("", stderr, &[]);
+        assert_eq!(
+            info.warnings,
+            vec!["WARNING: sample.7z".to_string(), "Open WARNING: Cannot open the file as expected archive type".to_string()]
+        );
+    }
+
+    #[test]
+    fn derives_the_compression_ratio() {
+        let mut folder = file("docs/", 5000);
+        folder.is_dir = true;
+        let info = parse_archive_info(LISTING, "", &[file("a.txt", 3000), file("b.txt", 1000), folder]);
+        assert_eq!(info.compression_ratio, Some(0.25));
+        assert_eq!(parse_archive_info(LISTING, "", &[]).compression_ratio, None);
+    }
+
+    #[test]
+    fn survives_forged_sizes() {
+        let info = parse_archive_info(LISTING, "", &[file("a", u64::MAX), file("b", u64::MAX)]);
+        assert_eq!(info.compression_ratio, Some(1000.0 / u64::MAX as f64));
+    }
+}
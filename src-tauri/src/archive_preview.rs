@@ -0,0 +1,248 @@
+//! Previews of single archive entries, read through `7z e -so` without extracting anything to disk.
+//! 通过 `7z e -so` 读取单个压缩包条目的预览，不向磁盘解压任何内容。
+
+use std::io::Read;
+use std::path::Path;
+use std::process::Stdio;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use encoding_rs::{Encoding, GBK};
+use serde::Serialize;
+
+use super::logging::log_info;
+use super::archive_utils::{new_7z_command, password_switch, spawn_error};
+use super::error::SoarZipError;
+
+/// How many bytes of an entry are read for a preview unless the caller asks otherwise
+/// 除非调用方另有要求，预览时读取条目的字节数
+pub const DEFAULT_PREVIEW_MAX_BYTES: u64 = 4 * 1024 * 1024;
+
+/// How many bytes of a binary entry the hex dump shows / 二进制条目的十六进制转储显示的字节数
+const HEX_DUMP_BYTES: usize = 4096;
+
+/// How many leading bytes are inspected to tell text from binary data / 用于区分文本和二进制数据的开头字节数
+const SNIFF_BYTES: usize = 8192;
+
+/// The preview of an entry, as shown by the frontend's preview pane.
+/// 条目的预览，由前端的预览面板显示。
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EntryPreview {
+    /// Decoded text / 已解码的文本
+    Text {
+        /// The decoded text.
+        text: String,
+        /// The detected encoding, e.g. `UTF-8`, `UTF-16LE` or `GBK`.
+        encoding: String,
+        /// Whether the entry is larger than the size cap and only its beginning is shown.
+        truncated: bool,
+    },
+    /// An image the frontend can display / 前端可以显示的图片
+    Image {
+        /// The MIME type, e.g. `image/png`.
+        mime_type: String,
+        /// The image bytes, base64-encoded.
+        data: String,
+    },
+    /// Anything else, shown as a hex dump of its beginning / 其他内容，显示为其开头部分的十六进制转储
+    Binary {
+        /// `offset  hex bytes  |ascii|` lines.
+        hex_dump: String,
+        /// The detected MIME type, if the content has a known signature.
+        mime_type: Option<String>,
+        /// Whether the entry is longer than the hex dump.
+        truncated: bool,
+    },
+}
+
+/// Reads the beginning of an entry by streaming it through `7z e -so`.
+/// Once `max_bytes` have been read, 7-Zip is stopped instead of decompressing the rest.
+///
+/// # Arguments
+///
+/// * `seven_zip_path` - The path to the 7-Zip executable.
+/// * `archive_path` - The archive containing the entry.
+/// * `entry_name` - The path of the entry inside the archive.
+/// * `password` - Optional password for encrypted archives.
+/// * `max_bytes` - The most bytes to read.
+///
+/// # Returns
+///
+/// * `Ok((Vec<u8>, bool))` - The bytes read, and whether the entry is longer than that.
+/// * `Err(SoarZipError)` - If 7-Zip failed, e.g. `PASSWORD_REQUIRED` for a wrong or missing password.
+///
+/// 通过 `7z e -so` 流式读取条目的开头部分。读取到 `max_bytes` 字节后即停止 7-Zip，而不是解压其余部分。
+///
+/// # 参数
+///
+/// * `seven_zip_path` - 7-Zip 可执行文件的路径。
+/// * `archive_path` - 包含该条目的压缩包。
+/// * `entry_name` - 条目在压缩包内的路径。
+/// * `password` - 用于加密压缩包的可选密码。
+/// * `max_bytes` - 最多读取的字节数。
+///
+/// # 返回值
+///
+/// * `Ok((Vec<u8>, bool))` - 读取到的字节，以及条目是否比这更长。
+/// * `Err(SoarZipError)` - 如果 7-Zip 失败，例如密码错误或缺失时返回 `PASSWORD_REQUIRED`。
+pub fn read_entry_bytes(
+    seven_zip_path: &Path,
+    archive_path: &str,
+    entry_name: &str,
+    password: Option<&str>,
+    max_bytes: u64,
+) -> Result<(Vec<u8>, bool), SoarZipError> {
+    // '-spd' takes the name literally, so '*' or '?' in it never selects other entries
+    // '-spd' 按字面处理名称，因此其中的 '*' 或 '?' 永远不会选中其他条目
+    let mut args = vec!["e".to_string(), "-so".to_string(), "-spd".to_string()];
+    args.extend(password_switch(password));
+    args.push(archive_path.to_string());
+    args.push("--".to_string());
+    args.push(entry_name.to_string());
+
+    let mut child = new_7z_command(seven_zip_path, &args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(spawn_error)?;
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+
+    // One byte more than the cap tells whether anything was left out / 比上限多读一个字节，以判断是否有内容被省略
+    let mut bytes = Vec::new();
+    let read = stdout.by_ref().take(max_bytes.saturating_add(1)).read_to_end(&mut bytes);
+    if let Err(e) = read {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(SoarZipError::Io(format!("Failed to read '{}' from the archive: {}", entry_name, e)));
+    }
+    if bytes.len() as u64 > max_bytes {
+        // The rest is not needed, so 7-Zip is stopped rather than drained / 其余部分不需要，因此停止 7-Zip 而不是读完
+        let _ = child.kill();
+        let _ = child.wait();
+        bytes.truncate(max_bytes as usize);
+        log_info(&format!("Read the first {} bytes of '{}' for a preview", bytes.len(), entry_name));
+        return Ok((bytes, true));
+    }
+    drop(stdout);
+
+    let output = child.wait_with_output().map_err(spawn_error)?;
+    if !output.status.success() {
+        return Err(SoarZipError::from_7z_output(&output, "read entry"));
+    }
+    log_info(&format!("Read all {} bytes of '{}' for a preview", bytes.len(), entry_name));
+    Ok((bytes, false))
+}
+
+/// Turns the bytes read from an entry into a preview.
+///
+/// Images are recognized by their signature (SVG by its extension) and returned whole; an image
+/// cut off by the size cap cannot be displayed, so it falls back to a hex dump. Other content is
+/// decoded as text if it looks like text, otherwise it is shown as a hex dump.
+///
+/// # Arguments
+///
+/// * `entry_name` - The path of the entry inside the archive.
+/// * `bytes` - The bytes read by [`read_entry_bytes`].
+/// * `truncated` - Whether the entry is longer than `bytes`.
+///
+/// 将从条目读取的字节转换为预览。
+///
+/// 图片通过其签名识别（SVG 通过扩展名识别）并完整返回；被大小上限截断的图片无法显示，因此退回为十六进制转储。
+/// 其他内容如果看起来像文本则按文本解码，否则显示为十六进制转储。
+///
+/// # 参数
+///
+/// * `entry_name` - 条目在压缩包内的路径。
+/// * `bytes` - 由 [`read_entry_bytes`] 读取的字节。
+/// * `truncated` - 条目是否比 `bytes` 更长。
+pub fn build_preview(entry_name: &str, bytes: &[u8], truncated: bool) -> EntryPreview {
+    let mut mime_type = infer::get(bytes).map(|kind| (kind.mime_type().to_string(), kind.matcher_type()));
+    let is_svg = Path::new(entry_name)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"));
+    if is_svg && mime_type.is_none() {
+        mime_type = Some(("image/svg+xml".to_string(), infer::MatcherType::Image));
+    }
+
+    if let Some((mime, infer::MatcherType::Image)) = &mime_type {
+        if !truncated {
+            return EntryPreview::Image { mime_type: mime.clone(), data: BASE64.encode(bytes) };
+        }
+    } else if let Some((text, encoding)) = decode_text(bytes, truncated) {
+        return EntryPreview::Text { text, encoding: encoding.to_string(), truncated };
+    }
+
+    let shown = &bytes[..bytes.len().min(HEX_DUMP_BYTES)];
+    EntryPreview::Binary {
+        hex_dump: hex_dump(shown),
+        mime_type: mime_type.map(|(mime, _)| mime),
+        truncated: truncated || shown.len() < bytes.len(),
+    }
+}
+
+/// Decodes bytes as text, or returns `None` if they look like binary data.
+///
+/// A byte order mark decides the encoding when present. Otherwise UTF-8 is tried first, then GBK,
+/// the same fallback used for 7-Zip's console output.
+///
+/// 将字节解码为文本；如果看起来是二进制数据则返回 `None`。
+///
+/// 存在字节顺序标记时由其决定编码。否则先尝试 UTF-8，再尝试 GBK，与 7-Zip 控制台输出使用的回退方式相同。
+pub fn decode_text(bytes: &[u8], truncated: bool) -> Option<(String, &'static str)> {
+    if let Some((encoding, bom_length)) = Encoding::for_bom(bytes) {
+        let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_length..]);
+        return Some((text.into_owned(), encoding.name()));
+    }
+
+    let sample = &bytes[..bytes.len().min(SNIFF_BYTES)];
+    if sample.contains(&0) {
+        return None;
+    }
+
+    // The size cap may cut the last character in half / 大小上限可能把最后一个字符截成两半
+    let (text, encoding) = match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_string(), "UTF-8"),
+        Err(e) if truncated && e.error_len().is_none() => {
+            (String::from_utf8_lossy(&bytes[..e.valid_up_to()]).into_owned(), "UTF-8")
+        }
+        Err(_) => {
+            let decoded = GBK.decode_without_bom_handling_and_without_replacement(bytes).or_else(|| {
+                let shortened = bytes.len().checked_sub(1).filter(|_| truncated)?;
+                GBK.decode_without_bom_handling_and_without_replacement(&bytes[..shortened])
+            })?;
+            (decoded.into_owned(), GBK.name())
+        }
+    };
+    looks_like_text(&text).then_some((text, encoding))
+}
+
+/// Whether decoded text is mostly printable; control characters other than whitespace
+/// are rare in real text but common in binary data that happens to decode.
+///
+/// 解码后的文本是否大部分可打印；除空白外的控制字符在真实文本中很少见，
+/// 但在恰好能被解码的二进制数据中很常见。
+fn looks_like_text(text: &str) -> bool {
+    let sample = text.chars().take(SNIFF_BYTES);
+    let (total, control) = sample.fold((0usize, 0usize), |(total, control), c| {
+        let is_control = c.is_control() && !matches!(c, '\n' | '\r' | '\t' | '\u{0c}' | '\u{1b}');
+        (total + 1, control + usize::from(is_control))
+    });
+    control * 100 <= total
+}
+
+/// Formats bytes as `offset  hex bytes  |ascii|` lines of 16 bytes each.
+/// 将字节格式化为每行 16 字节的 `偏移  十六进制字节  |ASCII|` 行。
+fn hex_dump(bytes: &[u8]) -> String {
+    bytes
+        .chunks(16)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|byte| format!("{:02x}", byte)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' })
+                .collect();
+            format!("{:08x}  {:<47}  |{}|\n", row * 16, hex.join(" "), ascii)
+        })
+        .collect()
+}
@@ -0,0 +1,575 @@
+//! Searching the content of archive entries (like `grep`). Entries are streamed one at a time
+//! through `7z e -so`; the selection of a solid archive is extracted to a temporary folder in a
+//! single pass instead.
+//!
+//! 搜索压缩包条目的内容（类似 `grep`）。条目会通过 `7z e -so` 逐个流式读取；
+//! 固实压缩包的所选内容则会一次性解压到临时文件夹中。
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Output, Stdio};
+use std::time::Duration;
+use regex::{Regex, RegexBuilder};
+use serde::{Serialize, Deserialize};
+
+use super::file_item::FileItem;
+use super::logging::{log_info, log_error};
+use super::archive_utils::{check_7z_output, create_temp_dir, new_7z_command, password_switch, run_with_list_file, spawn_error};
+use super::archive_extract::{ExtractionLimits, OutputSizeWatchdog, check_disk_space, check_extraction_limits, find_unsafe_entries};
+use super::archive_preview::decode_text;
+use super::progress::{ProgressReporter, run_7z_with_progress};
+use super::jobs::JobHandle;
+use super::error::SoarZipError;
+
+/// Name of the Tauri event that carries [`SearchMatch`] payloads to the frontend while a search runs.
+/// 在搜索运行期间将 [`SearchMatch`] 负载传递给前端的 Tauri 事件名称。
+pub const SEARCH_MATCH_EVENT: &str = "archive-search-match";
+
+/// The longest line excerpt sent with a match, in characters; minified files can have lines of
+/// several megabytes / 随匹配发送的行摘录的最大字符数；压缩过的文件的一行可能有好几兆字节
+const MAX_EXCERPT_CHARS: usize = 400;
+
+/// Options of a content search. Every field has a default, so the frontend may omit any of them.
+/// 内容搜索的选项。每个字段都有默认值，因此前端可以省略其中任何一个。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SearchOptions {
+    /// Treat the pattern as a regular expression instead of a plain substring.
+    pub regex: bool,
+    /// Match upper and lower case exactly.
+    pub case_sensitive: bool,
+    /// Entries larger than this many bytes are skipped.
+    pub max_file_size: u64,
+    /// How many lines before and after a matching line are sent along with it.
+    pub context_lines: usize,
+    /// The search stops after this many matching lines.
+    pub max_matches: usize,
+    /// Entries or folders to search; empty for the whole archive.
+    pub paths: Vec<String>,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        SearchOptions {
+            regex: false,
+            case_sensitive: false,
+            max_file_size: 16 * 1024 * 1024,
+            context_lines: 2,
+            max_matches: 1000,
+            paths: Vec::new(),
+        }
+    }
+}
+
+/// A line of an entry that matches the search pattern.
+/// 条目中与搜索模式匹配的一行。
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchMatch {
+    /// The id of the search, usable with `cancel_operation`.
+    pub job_id: String,
+    /// The path of the entry inside the archive.
+    pub entry_path: String,
+    /// The line number, starting at 1.
+    pub line_number: usize,
+    /// The column of the first match in the line, in characters, starting at 1.
+    pub column: usize,
+    /// The matching line, shortened around the match if it is very long.
+    pub line: String,
+    /// The lines before the matching line.
+    pub context_before: Vec<String>,
+    /// The lines after the matching line.
+    pub context_after: Vec<String>,
+}
+
+/// Summary of a finished search.
+/// 已完成搜索的摘要。
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchReport {
+    /// The number of entries whose content was searched.
+    pub entries_searched: usize,
+    /// The number of entries with at least one match.
+    pub entries_matched: usize,
+    /// The number of matching lines.
+    pub match_count: usize,
+    /// Entries skipped because they are larger than `max_file_size`.
+    pub skipped_too_large: Vec<String>,
+    /// Entries skipped because their content is not text.
+    pub skipped_binary: Vec<String>,
+    /// Entries that could not be read, e.g. because of an unsupported method.
+    pub failed: Vec<String>,
+    /// Whether the search stopped early at `max_matches`.
+    pub limit_reached: bool,
+}
+
+/// Builds the matcher for a pattern: a regular expression, or an escaped substring.
+///
+/// 为模式构建匹配器：正则表达式，或经过转义的子字符串。
+pub fn build_matcher(pattern: &str, options: &SearchOptions) -> Result<Regex, SoarZipError> {
+    if pattern.is_empty() {
+        return Err(SoarZipError::InvalidInput("The search pattern is empty.".to_string()));
+    }
+    let source = if options.regex { pattern.to_string() } else { regex::escape(pattern) };
+    RegexBuilder::new(&source)
+        .case_insensitive(!options.case_sensitive)
+        .build()
+        .map_err(|e| SoarZipError::InvalidInput(format!("Invalid regular expression '{}': {}", pattern, e)))
+}
+
+/// Searches the content of archive entries one by one, handing every matching line to `on_match`
+/// as soon as it is found.
+///
+/// Folders, entries above the size limit and entries that do not decode as text are skipped.
+/// In a solid archive each entry can only be decompressed from the start of its block, so the
+/// entries are extracted to a temporary folder in one 7-Zip run first rather than streamed one by one.
+/// Every 7-Zip process is attached to the job, so cancelling stops the search even in the middle of an entry.
+///
+/// # Arguments
+///
+/// * `seven_zip_path` - The path to the 7-Zip executable.
+/// * `archive_path` - The archive to search.
+/// * `entries` - The entries to search, usually the selection of the listing.
+/// * `password` - Optional password for encrypted archives.
+/// * `solid` - Whether the archive is solid.
+/// * `matcher` - The matcher built by [`build_matcher`].
+/// * `options` - The search options.
+/// * `job` - The handle used to detect cancellation and to stop 7-Zip.
+/// * `on_match` - Receives every match.
+///
+/// # Returns
+///
+/// * `Ok(SearchReport)` - The summary of the search.
+/// * `Err(SoarZipError)` - `Cancelled` if the search was cancelled, `PASSWORD_REQUIRED` for a wrong
+///   or missing password, or another error if 7-Zip cannot be run.
+///
+/// 逐个搜索压缩包条目的内容，每找到一个匹配行就立即交给 `on_match`。
+///
+/// 会跳过文件夹、超过大小限制的条目以及无法解码为文本的条目。固实压缩包中的每个条目只能从其所在块的开头解压，
+/// 因此会先通过一次 7-Zip 运行将这些条目解压到临时文件夹中，而不是逐个流式读取。
+/// 每个 7-Zip 进程都关联到作业，因此即使正在读取某个条目，取消也能停止搜索。
+///
+/// # 参数
+///
+/// * `seven_zip_path` - 7-Zip 可执行文件的路径。
+/// * `archive_path` - 要搜索的压缩包。
+/// * `entries` - 要搜索的条目，通常是列表中的所选内容。
+/// * `password` - 用于加密压缩包的可选密码。
+/// * `solid` - 压缩包是否为固实压缩包。
+/// * `matcher` - 由 [`build_matcher`] 构建的匹配器。
+/// * `options` - 搜索选项。
+/// * `job` - 用于检测取消并停止 7-Zip 的句柄。
+/// * `on_match` - 接收每个匹配。
+///
+/// # 返回值
+///
+/// * `Ok(SearchReport)` - 搜索摘要。
+/// * `Err(SoarZipError)` - 如果搜索被取消则返回 `Cancelled`；密码错误或缺失时返回 `PASSWORD_REQUIRED`；
+///   如果无法运行 7-Zip 则返回其他错误。
+#[allow(clippy::too_many_arguments)]
+pub fn search_entries<F>(
+    seven_zip_path: &Path,
+    archive_path: &str,
+    entries: &[&FileItem],
+    password: Option<&str>,
+    solid: bool,
+    matcher: &Regex,
+    options: &SearchOptions,
+    job: &JobHandle,
+    mut on_match: F,
+) -> Result<SearchReport, SoarZipError>
+where
+    F: FnMut(SearchMatch),
+{
+    let mut report = SearchReport::default();
+    let mut candidates = Vec::new();
+    for item in entries.iter().filter(|item| !item.is_dir) {
+        if item.size > options.max_file_size {
+            report.skipped_too_large.push(item.name.clone());
+        } else {
+            candidates.push(*item);
+        }
+    }
+
+    // Removed with everything in it when the search ends / 搜索结束时连同其中的所有内容一起被删除
+    let extracted = if solid && candidates.len() > 1 {
+        Some(ExtractedEntries::extract(seven_zip_path, archive_path, &candidates, password, options.max_file_size, job)?)
+    } else {
+        None
+    };
+
+    for item in candidates {
+        if job.is_cancelled() {
+            log_info(&format!("Search in {} cancelled after {} entries", archive_path, report.entries_searched));
+            return Err(job.stop_error());
+        }
+
+        // Entries that were not extracted, e.g. links or unsafe paths, are streamed
+        // 未被解压的条目（例如链接或不安全的路径）会被流式读取
+        let read = match extracted.as_ref().and_then(|extracted| extracted.file_for(item)) {
+            Some(path) => read_extracted_file(&path, options.max_file_size),
+            None => read_entry_streamed(seven_zip_path, archive_path, &item.name, password, options.max_file_size, job),
+        };
+        let (bytes, truncated) = match read {
+            Ok(read) => read,
+            Err(_) if job.is_cancelled() => return Err(job.stop_error()),
+            Err(SoarZipError::PasswordRequired) => return Err(SoarZipError::PasswordRequired),
+            Err(e) => {
+                log_error(&format!("Failed to read '{}' for searching: {}", item.name, e));
+                report.failed.push(item.name.clone());
+                continue;
+            }
+        };
+        // The listing may understate the size / 列表中的大小可能偏小
+        if truncated {
+            report.skipped_too_large.push(item.name.clone());
+            continue;
+        }
+        let Some((text, _)) = decode_text(&bytes, false) else {
+            report.skipped_binary.push(item.name.clone());
+            continue;
+        };
+
+        report.entries_searched += 1;
+        let remaining = options.max_matches.saturating_sub(report.match_count);
+        let matches = find_matches(&text, matcher, options.context_lines, remaining);
+        if !matches.is_empty() {
+            report.entries_matched += 1;
+        }
+        for found in matches {
+            report.match_count += 1;
+            on_match(SearchMatch { job_id: job.id().to_string(), entry_path: item.name.clone(), ..found });
+        }
+        if report.match_count >= options.max_matches {
+            report.limit_reached = true;
+            break;
+        }
+    }
+
+    log_info(&format!(
+        "Searched {} entries of {}: {} matches in {} entries",
+        report.entries_searched, archive_path, report.match_count, report.entries_matched
+    ));
+    Ok(report)
+}
+
+/// The selection of a solid archive, extracted to a temporary folder that is removed when dropped.
+/// 固实压缩包的所选内容，被解压到一个临时文件夹中，该文件夹在被丢弃时删除。
+struct ExtractedEntries {
+    path: PathBuf,
+    /// Entries left out because their paths would escape the folder / 因路径会逃出该文件夹而被排除的条目
+    unsafe_names: HashSet<String>,
+}
+
+impl ExtractedEntries {
+    /// Extracts the entries in a single 7-Zip run attached to the job, under the default extraction
+    /// limits, which are checked against the listing first. During the run the written data may not
+    /// exceed `max_file_size` per entry.
+    /// A run that fails for some entries still counts: whatever was not written is streamed later.
+    ///
+    /// 在关联到作业的一次 7-Zip 运行中解压这些条目，并遵守默认的解压限制，这些限制会先根据列表检查。
+    /// 运行期间写入的数据平均每个条目不得超过 `max_file_size`。部分条目失败的运行仍然有效：未写入的内容稍后会被流式读取。
+    fn extract(
+        seven_zip_path: &Path,
+        archive_path: &str,
+        entries: &[&FileItem],
+        password: Option<&str>,
+        max_file_size: u64,
+        job: &JobHandle,
+    ) -> Result<Self, SoarZipError> {
+        let unsafe_names: HashSet<String> = find_unsafe_entries(entries).into_iter().map(|entry| entry.path).collect();
+        let names: Vec<String> = entries
+            .iter()
+            .filter(|item| !unsafe_names.contains(&item.name))
+            .map(|item| item.name.clone())
+            .collect();
+        let extracted = ExtractedEntries { path: create_temp_dir("search")?, unsafe_names };
+        if names.is_empty() {
+            return Ok(extracted);
+        }
+
+        // The sizes come from the archive headers and may be forged / 大小来自压缩包头部，可能是伪造的
+        let limits = ExtractionLimits::default();
+        check_extraction_limits(entries, &limits)?;
+        let total_bytes = entries.iter().fold(0u64, |sum, item| sum.saturating_add(item.size));
+        check_disk_space(&extracted.path, total_bytes, 0)?;
+        // Every entry is listed at or below the file size limit, so more than that per entry is a bomb
+        // 每个条目在列表中都不超过文件大小限制，因此每个条目超出该值的数据即为炸弹
+        let max_written = max_file_size.saturating_mul(names.len() as u64);
+        let max_written = limits.max_total_size.map_or(max_written, |max_total_size| max_total_size.min(max_written));
+        let watchdog = OutputSizeWatchdog::start(&extracted.path, &names, max_written, job.clone());
+        let mut reporter = ProgressReporter::new(job.id(), "search", total_bytes, Box::new(|_| {}));
+        let output = run_with_list_file(&names, |list_file_arg| {
+            let mut args = vec![
+                "x".to_string(),
+                archive_path.to_string(),
+                format!("-o{}", extracted.path.display()),
+                "-aoa".to_string(),
+                "-scsUTF-8".to_string(),
+                "-spd".to_string(), // The listed names are not wildcards / 列出的名称不是通配符
+            ];
+            args.extend(password_switch(password));
+            args.push(list_file_arg.to_string());
+            run_7z_with_progress(seven_zip_path, &args, None, &mut reporter, job)
+        })?;
+        drop(watchdog);
+        match check_7z_output(&output, "search") {
+            Ok(_) => log_info(&format!("Extracted {} entries of solid archive {} for searching", names.len(), archive_path)),
+            Err(SoarZipError::PasswordRequired) => return Err(SoarZipError::PasswordRequired),
+            Err(e) => log_error(&format!("Some entries of {} could not be extracted for searching: {}", archive_path, e)),
+        }
+        Ok(extracted)
+    }
+
+    /// The extracted file of an entry, if it was written as a regular file.
+    /// 条目被解压出的文件（如果它被写为普通文件）。
+    fn file_for(&self, item: &FileItem) -> Option<PathBuf> {
+        if self.unsafe_names.contains(&item.name) {
+            return None;
+        }
+        let path = self.path.join(&item.name);
+        path.symlink_metadata().is_ok_and(|metadata| metadata.is_file()).then_some(path)
+    }
+}
+
+impl Drop for ExtractedEntries {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.path) {
+            log_error(&format!("Failed to remove search directory '{}': {}", self.path.display(), e));
+        }
+    }
+}
+
+/// Reads up to `max_bytes` of an extracted file, returning whether it is longer than that.
+/// 读取已解压文件的最多 `max_bytes` 个字节，并返回文件是否比这更长。
+fn read_extracted_file(path: &Path, max_bytes: u64) -> Result<(Vec<u8>, bool), SoarZipError> {
+    let file = File::open(path).map_err(|e| SoarZipError::Io(format!("Failed to open '{}': {}", path.display(), e)))?;
+    let mut bytes = Vec::new();
+    file.take(max_bytes.saturating_add(1))
+        .read_to_end(&mut bytes)
+        .map_err(|e| SoarZipError::Io(format!("Failed to read '{}': {}", path.display(), e)))?;
+    let truncated = bytes.len() as u64 > max_bytes;
+    bytes.truncate(max_bytes as usize);
+    Ok((bytes, truncated))
+}
+
+/// Reads up to `max_bytes` of an entry through `7z e -so`, like
+/// [`read_entry_bytes`](super::archive_preview::read_entry_bytes), but with the process attached
+/// to the job so that cancelling kills it.
+///
+/// 像 [`read_entry_bytes`](super::archive_preview::read_entry_bytes) 一样通过 `7z e -so` 读取条目的最多
+/// `max_bytes` 个字节，但进程会关联到作业，以便取消时将其终止。
+fn read_entry_streamed(
+    seven_zip_path: &Path,
+    archive_path: &str,
+    entry_name: &str,
+    password: Option<&str>,
+    max_bytes: u64,
+    job: &JobHandle,
+) -> Result<(Vec<u8>, bool), SoarZipError> {
+    // '-spd' takes the name literally, so '*' or '?' in it never selects other entries
+    // '-spd' 按字面处理名称，因此其中的 '*' 或 '?' 永远不会选中其他条目
+    let mut args = vec!["e".to_string(), "-so".to_string(), "-spd".to_string()];
+    args.extend(password_switch(password));
+    args.push(archive_path.to_string());
+    args.push("--".to_string());
+    args.push(entry_name.to_string());
+
+    let mut child = new_7z_command(seven_zip_path, &args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(spawn_error)?;
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let mut stderr_pipe = child.stderr.take().expect("stderr is piped");
+    job.attach_child(child);
+
+    // Drain stderr on its own thread so a full pipe can never block 7-Zip
+    // 在单独的线程中读取 stderr，避免管道写满阻塞 7-Zip
+    let stderr_thread = std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = stderr_pipe.read_to_end(&mut buffer);
+        buffer
+    });
+
+    // One byte more than the cap tells whether anything was left out; closing the pipe then makes
+    // 7-Zip fail at its next write instead of decompressing the rest
+    // 比上限多读一个字节，以判断是否有内容被省略；随后关闭管道，使 7-Zip 在下一次写入时失败，而不是解压其余部分
+    let mut bytes = Vec::new();
+    let read = stdout.by_ref().take(max_bytes.saturating_add(1)).read_to_end(&mut bytes);
+    drop(stdout);
+
+    // Poll instead of blocking in wait() so the job lock stays free for cancel_operation
+    // 轮询而不是阻塞在 wait() 中，使作业锁对 cancel_operation 保持可用
+    let status = loop {
+        if let Some(status) = job.try_wait_child()? {
+            break status;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    let stderr = stderr_thread.join().unwrap_or_default();
+
+    if job.is_cancelled() {
+        return Err(job.stop_error());
+    }
+    if let Err(e) = read {
+        return Err(SoarZipError::Io(format!("Failed to read '{}' from the archive: {}", entry_name, e)));
+    }
+    if bytes.len() as u64 > max_bytes {
+        bytes.truncate(max_bytes as usize);
+        return Ok((bytes, true));
+    }
+    if !status.success() {
+        return Err(SoarZipError::from_7z_output(&Output { status, stdout: Vec::new(), stderr }, "read entry"));
+    }
+    Ok((bytes, false))
+}
+
+/// Finds up to `limit` matching lines in a text. The returned matches have no job id or entry path yet.
+///
+/// 在文本中查找最多 `limit` 个匹配行。返回的匹配尚未填写作业 id 和条目路径。
+fn find_matches(text: &str, matcher: &Regex, context_lines: usize, limit: usize) -> Vec<SearchMatch> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut matches = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if matches.len() >= limit {
+            break;
+        }
+        let Some(found) = matcher.find(line) else {
+            continue;
+        };
+        let before = &lines[index.saturating_sub(context_lines)..index];
+        let after = &lines[index + 1..lines.len().min(index + 1 + context_lines)];
+        matches.push(SearchMatch {
+            job_id: String::new(),
+            entry_path: String::new(),
+            line_number: index + 1,
+            column: line[..found.start()].chars().count() + 1,
+            line: excerpt(line, found.start()),
+            context_before: before.iter().map(|l| excerpt(l, 0)).collect(),
+            context_after: after.iter().map(|l| excerpt(l, 0)).collect(),
+        });
+    }
+    matches
+}
+
+/// Shortens a long line to [`MAX_EXCERPT_CHARS`] characters, keeping the part around the byte
+/// offset `focus` and marking cut ends with `…`.
+///
+/// 将过长的行缩短为 [`MAX_EXCERPT_CHARS`] 个字符，保留字节偏移 `focus` 附近的部分，并用 `…` 标记被截断的两端。
+fn excerpt(line: &str, focus: usize) -> String {
+    let total = line.chars().count();
+    if total <= MAX_EXCERPT_CHARS {
+        return line.to_string();
+    }
+    let focus_char = line[..focus].chars().count();
+    let start = focus_char.saturating_sub(MAX_EXCERPT_CHARS / 4).min(total - MAX_EXCERPT_CHARS);
+    let end = start + MAX_EXCERPT_CHARS;
+    let mut shortened: String = line.chars().skip(start).take(MAX_EXCERPT_CHARS).collect();
+    if start > 0 {
+        shortened.insert(0, '…');
+    }
+    if end < total {
+        shortened.push('…');
+    }
+    shortened
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_EXCERPT_CHARS, SearchOptions, SoarZipError, build_matcher, excerpt, find_matches};
+
+    const README: &str = "\
+# Soar Zip
+A small archive manager.
+
+Extracting: 7z x archive.7z
+Testing: 7z t archive.7z
+Listing: 7z l -slt archive.7z
+";
+
+    #[test]
+    fn plain_patterns_match_literally_and_ignore_case() {
+        let matcher = build_matcher("7Z X", &SearchOptions::default()).unwrap();
+        assert!(matcher.is_match("Extracting: 7z x archive.7z"));
+        let matcher = build_matcher("a.c", &SearchOptions::default()).unwrap();
+        assert!(!matcher.is_match("abc"));
+        assert!(matcher.is_match("a.c"));
+    }
+
+    #[test]
+    fn regex_and_case_sensitive_options_are_applied() {
+        let options = SearchOptions { regex: true, case_sensitive: true, ..SearchOptions::default() };
+        let matcher = build_matcher(r"7z [xt] ", &options).unwrap();
+        assert!(matcher.is_match("Testing: 7z t archive.7z"));
+        assert!(!matcher.is_match("Testing: 7Z T archive.7z"));
+    }
+
+    #[test]
+    fn rejects_empty_and_invalid_patterns() {
+        let regex = SearchOptions { regex: true, ..SearchOptions::default() };
+        assert!(matches!(build_matcher("", &SearchOptions::default()), Err(SoarZipError::InvalidInput(_))));
+        assert!(matches!(build_matcher("(unclosed", &regex), Err(SoarZipError::InvalidInput(_))));
+        assert!(build_matcher("(unclosed", &SearchOptions::default()).is_ok());
+    }
+
+    #[test]
+    fn finds_lines_with_context() {
+        let matcher = build_matcher("archive.7z", &SearchOptions::default()).unwrap();
+        let matches = find_matches(README, &matcher, 1, 10);
+        assert_eq!(matches.iter().map(|m| m.line_number).collect::<Vec<_>>(), vec![4, 5, 6]);
+        assert_eq!(matches[0].column, 18);
+        assert_eq!(matches[0].context_before, vec![""]);
+        assert_eq!(matches[0].context_after, vec!["Testing: 7z t archive.7z"]);
+        // The last line has no line after it / 最后一行之后没有其他行
+        assert!(matches[2].context_after.is_empty());
+    }
+
+    #[test]
+    fn stops_at_the_limit() {
+        let matcher = build_matcher("7z", &SearchOptions::default()).unwrap();
+        let matches = find_matches(README, &matcher, 0, 2);
+        assert_eq!(matches.len(), 2);
+        assert!(matches[0].context_before.is_empty() && matches[0].context_after.is_empty());
+    }
+
+    #[test]
+    fn counts_columns_in_characters() {
+        let matcher = build_matcher("压缩", &SearchOptions::default()).unwrap();
+        let matches = find_matches("第一行\n解压缩包", &matcher, 2, 10);
+        assert_eq!(matches.len(), 1);
+        assert_eq!((matches[0].line_number, matches[0].column), (2, 2));
+        assert_eq!(matches[0].context_before, vec!["第一行"]);
+    }
+
+    #[test]
+    fn keeps_short_lines() {
+        let line = "a".repeat(MAX_EXCERPT_CHARS);
+        assert_eq!(excerpt(&line, 200), line);
+    }
+
+    #[test]
+    fn shortens_long_lines_around_the_focus() {
+        let line = "a".repeat(1000);
+        let start = excerpt(&line, 0);
+        assert_eq!(start.chars().count(), MAX_EXCERPT_CHARS + 1);
+        assert!(!start.starts_with('…') && start.ends_with('…'));
+
+        let middle = excerpt(&line, 500);
+        assert_eq!(middle.chars().count(), MAX_EXCERPT_CHARS + 2);
+        assert!(middle.starts_with('…') && middle.ends_with('…'));
+
+        let end = excerpt(&line, 990);
+        assert_eq!(end.chars().count(), MAX_EXCERPT_CHARS + 1);
+        assert!(end.starts_with('…') && !end.ends_with('…'));
+    }
+
+    #[test]
+    fn shortens_multibyte_lines_on_character_boundaries() {
+        let line = format!("{}needle", "é".repeat(1000));
+        let shortened = excerpt(&line, line.find("needle").unwrap());
+        assert!(shortened.starts_with('…'));
+        assert!(shortened.ends_with("needle"));
+        assert_eq!(shortened.chars().count(), MAX_EXCERPT_CHARS + 1);
+    }
+}
@@ -0,0 +1,324 @@
+//! Integrity testing of archives through the bundled 7-Zip executable (`7z t`).
+//! 通过捆绑的 7-Zip 可执行文件（`7z t`）测试压缩包的完整性。
+
+use std::collections::HashSet;
+use std::path::Path;
+use serde::Serialize;
+
+use super::logging::{log_info, log_error};
+use super::archive_utils::{decode_7z_output, list_archive_entries, password_switch};
+use super::error::{SoarZipError, is_password_error};
+use super::progress::{ProgressReporter, run_7z_with_progress};
+use super::jobs::JobHandle;
+
+/// Why an entry failed the integrity test.
+/// 条目未通过完整性测试的原因。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestErrorKind {
+    /// The checksum of the unpacked data does not match / 解压数据的校验和不匹配
+    CrcFailed,
+    /// The packed data is corrupt / 压缩数据已损坏
+    DataError,
+    /// The archive ends before the entry does / 压缩包在条目结束前就已结束
+    UnexpectedEnd,
+    /// The entry is encrypted and the password is wrong / 条目已加密且密码错误
+    WrongPassword,
+    /// The entry uses a compression or encryption method 7-Zip cannot read / 条目使用了 7-Zip 无法读取的压缩或加密方法
+    UnsupportedMethod,
+    /// Any other error reported by 7-Zip / 7-Zip 报告的其他错误
+    Other,
+}
+
+impl TestErrorKind {
+    fn classify(message: &str) -> Self {
+        let lower = message.to_lowercase();
+        if lower.contains("crc failed") {
+            TestErrorKind::CrcFailed
+        } else if lower.contains("wrong password") {
+            TestErrorKind::WrongPassword
+        } else if lower.contains("data error") {
+            TestErrorKind::DataError
+        } else if lower.contains("unexpected end") {
+            TestErrorKind::UnexpectedEnd
+        } else if lower.contains("unsupported method") {
+            TestErrorKind::UnsupportedMethod
+        } else {
+            TestErrorKind::Other
+        }
+    }
+}
+
+/// One entry that failed the integrity test.
+/// 一个未通过完整性测试的条目。
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedEntry {
+    /// The path of the entry inside the archive.
+    pub path: String,
+    /// The classified error.
+    pub error: TestErrorKind,
+    /// The message as printed by 7-Zip.
+    pub message: String,
+}
+
+/// Overall result of an integrity test, for the UI to show.
+/// 完整性测试的总体结果，供 UI 显示。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestVerdict {
+    /// Every entry was read back without errors / 所有条目均无错误地读回
+    Ok,
+    /// The archive could be read, but some entries or structures are broken / 压缩包可以读取，但部分条目或结构已损坏
+    Damaged,
+    /// The archive could not be opened at all / 压缩包完全无法打开
+    Unreadable,
+}
+
+/// Structured report returned by [`test_archive_with_7z`].
+/// [`test_archive_with_7z`] 返回的结构化报告。
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveTestReport {
+    /// The tested archive.
+    pub archive_path: String,
+    /// The number of file entries in the archive (folders are not counted).
+    pub total_entries: usize,
+    /// The number of file entries that passed.
+    pub passed_entries: usize,
+    /// The entries that failed, with their errors.
+    pub failed_entries: Vec<FailedEntry>,
+    /// Errors that concern the archive as a whole (e.g., broken headers).
+    pub archive_errors: Vec<String>,
+    /// The overall verdict.
+    pub verdict: TestVerdict,
+}
+
+/// Tests an archive with `7z t` and builds a report from 7-Zip's per-file results.
+///
+/// # Arguments
+///
+/// * `seven_zip_path` - The path to the 7-Zip executable.
+/// * `archive_path` - The archive to test.
+/// * `password` - Optional password for encrypted archives.
+/// * `reporter` - Receives progress while 7-Zip runs.
+/// * `job` - The job the 7-Zip process belongs to, for cancellation.
+///
+/// # Returns
+///
+/// * `Ok(ArchiveTestReport)` - The report, also when the archive turned out to be damaged.
+/// * `Err(SoarZipError)` - `PasswordRequired` if the archive is encrypted and no password was given,
+///   `Cancelled` if the test was cancelled, or another error if 7-Zip could not run.
+///
+/// 使用 `7z t` 测试压缩包，并根据 7-Zip 的逐文件结果生成报告。
+///
+/// # 参数
+///
+/// * `seven_zip_path` - 7-Zip 可执行文件的路径。
+/// * `archive_path` - 要测试的压缩包。
+/// * `password` - 用于加密压缩包的可选密码。
+/// * `reporter` - 在 7-Zip 运行期间接收进度。
+/// * `job` - 7-Zip 进程所属的作业，用于取消。
+///
+/// # 返回值
+///
+/// * `Ok(ArchiveTestReport)` - 测试报告，压缩包损坏时同样返回报告。
+/// * `Err(SoarZipError)` - 如果压缩包已加密且未提供密码则返回 `PasswordRequired`；
+///   如果测试被取消则返回 `Cancelled`；如果 7-Zip 无法运行，则返回其他错误。
+pub fn test_archive_with_7z(
+    seven_zip_path: &Path,
+    archive_path: &str,
+    password: Option<&str>,
+    reporter: &mut ProgressReporter,
+    job: &JobHandle,
+) -> Result<ArchiveTestReport, SoarZipError> {
+    let mut report = ArchiveTestReport {
+        archive_path: archive_path.to_string(),
+        total_entries: 0,
+        passed_entries: 0,
+        failed_entries: Vec::new(),
+        archive_errors: Vec::new(),
+        verdict: TestVerdict::Ok,
+    };
+
+    // A listing that cannot be read means the archive is unreadable, not that the command failed
+    // 无法读取列表意味着压缩包不可读，而不是命令失败
+    let entries = match list_archive_entries(seven_zip_path, archive_path, password) {
+        Ok(entries) => entries,
+        Err(SoarZipError::PasswordRequired) => return Err(SoarZipError::PasswordRequired),
+        Err(e @ (SoarZipError::SevenZipMissing(_) | SoarZipError::Io(_))) => return Err(e),
+        Err(e) => {
+            report.archive_errors.push(e.to_string());
+            report.verdict = TestVerdict::Unreadable;
+            return Ok(report);
+        }
+    };
+    report.total_entries = entries.iter().filter(|item| !item.is_dir).count();
+
+    let mut args = vec!["t".to_string()];
+    args.extend(password_switch(password));
+    args.push(archive_path.to_string());
+    let output = run_7z_with_progress(seven_zip_path, &args, None, reporter, job)?;
+
+    let stdout_output = decode_7z_output(&output.stdout);
+    let stderr_output = decode_7z_output(&output.stderr);
+    if !output.status.success() && password.is_none() && is_password_error(&format!("{}\n{}", stdout_output, stderr_output)) {
+        return Err(SoarZipError::PasswordRequired);
+    }
+
+    for text in [&stdout_output, &stderr_output] {
+        let (failed, archive_errors) = parse_test_output(text);
+        report.failed_entries.extend(failed);
+        report.archive_errors.extend(archive_errors);
+    }
+    if !output.status.success() && report.failed_entries.is_empty() && report.archive_errors.is_empty() {
+        report.archive_errors.push(format!(
+            "7-Zip test command failed with exit code: {}. Error: {}",
+            output.status.code().unwrap_or(-1),
+            stderr_output.trim()
+        ));
+    }
+
+    let failed_paths: HashSet<&str> = report.failed_entries.iter().map(|entry| entry.path.as_str()).collect();
+    report.passed_entries = report.total_entries.saturating_sub(failed_paths.len());
+    report.verdict = if report.failed_entries.is_empty() && report.archive_errors.is_empty() {
+        TestVerdict::Ok
+    } else {
+        TestVerdict::Damaged
+    };
+
+    reporter.finish();
+    if report.verdict == TestVerdict::Ok {
+        log_info(&format!("Archive test passed: {} ({} entries)", archive_path, report.total_entries));
+    } else {
+        log_error(&format!(
+            "Archive test found problems: {} ({} failed entries, {} archive errors)",
+            archive_path,
+            report.failed_entries.len(),
+            report.archive_errors.len()
+        ));
+    }
+    Ok(report)
+}
+
+/// Extracts failed entries and archive-level errors from `7z t` output.
+///
+/// Entry errors look like `ERROR: CRC Failed : docs/a.txt`. Errors without an entry path,
+/// `Open ERROR:` lines and the lines below an `ERRORS:` heading concern the whole archive.
+///
+/// 从 `7z t` 输出中提取失败的条目和压缩包级别的错误。
+///
+/// 条目错误形如 `ERROR: CRC Failed : docs/a.txt`。没有条目路径的错误、`Open ERROR:` 行
+/// 以及 `ERRORS:` 标题下的各行都与整个压缩包有关。
+fn parse_test_output(output: &str) -> (Vec<FailedEntry>, Vec<String>) {
+    let mut failed = Vec::new();
+    let mut archive_errors = Vec::new();
+    let mut in_errors_block = false;
+
+    // Progress redraws use '\r' and backspaces instead of newlines / 进度重绘使用 '\r' 和退格符而不是换行符
+    for line in output.split(['\n', '\r', '\u{8}']) {
+        let line = line.trim();
+        if line.is_empty() {
+            in_errors_block = false;
+            continue;
+        }
+        if line == "ERRORS:" {
+            in_errors_block = true;
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("ERROR:") {
+            let rest = rest.trim();
+            match rest.split_once(" : ") {
+                Some((message, path)) => failed.push(FailedEntry {
+                    path: path.trim().replace('\\', "/"),
+                    error: TestErrorKind::classify(message),
+                    message: message.trim().to_string(),
+                }),
+                None => archive_errors.push(rest.to_string()),
+            }
+        } else if line.starts_with("Open ERROR:") || in_errors_block {
+            archive_errors.push(line.to_string());
+        }
+    }
+    (failed, archive_errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_test_output, TestErrorKind};
+
+    #[test]
+    fn reports_failed_entries() {
+        let output = "\
+7-Zip 23.01 (x64) : Copyright (c) 1999-2023 Igor Pavlov : 2023-06-20
+
+Scanning the drive for archives:
+1 file, 2048 bytes (2 KiB)
+
+Testing archive: broken.7z
+--
+Path = broken.7z
+Type = 7z
+
+ERROR: CRC Failed : docs\\a.txt
+ERROR: Data Error : images/b.png
+ERROR: Wrong password : secret.txt
+
+Sub items Errors: 3
+
+Archives with Errors: 1
+";
+        let (failed, archive_errors) = parse_test_output(output);
+        assert!(archive_errors.is_empty());
+        let summary: Vec<(&str, TestErrorKind)> = failed.iter().map(|entry| (entry.path.as_str(), entry.error)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("docs/a.txt", TestErrorKind::CrcFailed),
+                ("images/b.png", TestErrorKind::DataError),
+                ("secret.txt", TestErrorKind::WrongPassword),
+            ]
+        );
+        assert_eq!(failed[0].message, "CRC Failed");
+    }
+
+    #[test]
+    fn reports_archive_level_errors() {
+        let output = "\
+Testing archive: cut.zip
+ERROR: cut.zip
+Open ERROR: Can not open the file as [zip] archive
+
+ERRORS:
+Unexpected end of archive
+Headers Error
+
+Can't open as archive: 1
+";
+        let (failed, archive_errors) = parse_test_output(output);
+        assert!(failed.is_empty());
+        assert_eq!(
+            archive_errors,
+            vec![
+                "cut.zip".to_string(),
+                "Open ERROR: Can not open the file as [zip] archive".to_string(),
+                "Unexpected end of archive".to_string(),
+                "Headers Error".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_progress_redraws() {
+        let output = " 40% - a.txt\r\u{8}\u{8}ERROR: Unsupported Method : b.bin\r 100%";
+        let (failed, _) = parse_test_output(output);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].path, "b.bin");
+        assert_eq!(failed[0].error, TestErrorKind::UnsupportedMethod);
+    }
+
+    #[test]
+    fn accepts_a_clean_run() {
+        let (failed, archive_errors) = parse_test_output("Testing archive: ok.7z\n\nEverything is Ok\n\nFiles: 3\n");
+        assert!(failed.is_empty());
+        assert!(archive_errors.is_empty());
+    }
+}
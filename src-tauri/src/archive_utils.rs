@@ -4,6 +4,7 @@
 use std::process::{Command, Output, Stdio};
 use std::path::{Path, PathBuf};
 use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::{AppHandle, Manager}; // Add AppHandle and Manager for resource access
 
 #[cfg(target_os = "windows")]
@@ -12,7 +13,9 @@ use std::os::windows::process::CommandExt;
 use encoding_rs;
 
 use super::file_item::FileItem; // Import FileItem from the parent module
+use super::archive_info::{OpenedArchive, parse_archive_info};
 use super::logging::{log_info, log_error}; // Import logging functions
+use super::error::SoarZipError;
 
 /// Determines the relative path to the bundled 7-Zip executable based on the target OS.
 /// Returns the path relative to the application's resource directory.
@@ -20,7 +23,7 @@ use super::logging::{log_info, log_error}; // Import logging functions
 /// # Returns
 ///
 /// * `Ok(String)` - The relative path to the 7-Zip executable.
-/// * `Err(String)` - An error message if the OS is unsupported.
+/// * `Err(SoarZipError)` - `SevenZipMissing` if the OS is unsupported.
 ///
 /// 根据目标操作系统确定捆绑的 7-Zip 可执行文件的相对路径。
 /// 返回相对于应用程序资源目录的路径。
@@ -28,8 +31,8 @@ use super::logging::{log_info, log_error}; // Import logging functions
 /// # 返回值
 ///
 /// * `Ok(String)` - 7-Zip 可执行文件的相对路径。
-/// * `Err(String)` - 如果操作系统不受支持，则返回错误消息。
-fn get_7z_resource_path() -> Result<String, String> {
+/// * `Err(SoarZipError)` - 如果操作系统不受支持，则返回 `SevenZipMissing`。
+fn get_7z_resource_path() -> Result<String, SoarZipError> {
     #[cfg(target_os = "windows")]
     { Ok("binaries/win/7z.exe".to_string()) }
     #[cfg(target_os = "macos")]
@@ -38,7 +41,7 @@ fn get_7z_resource_path() -> Result<String, String> {
     { Ok("binaries/linux/7z".to_string()) }
     // Catch-all for unsupported OS
     #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
-    { Err("Unsupported operating system for bundled 7-Zip.".to_string()) }
+    { Err(SoarZipError::SevenZipMissing("Unsupported operating system for bundled 7-Zip.".to_string())) }
 }
 
 /// Resolves the full path to the bundled 7-Zip executable.
@@ -50,7 +53,7 @@ fn get_7z_resource_path() -> Result<String, String> {
 /// # Returns
 ///
 /// * `Ok(PathBuf)` - The absolute path to the 7-Zip executable.
-/// * `Err(String)` - An error message if the path cannot be resolved or the executable doesn't exist.
+/// * `Err(SoarZipError)` - `SevenZipMissing` if the path cannot be resolved or the executable doesn't exist.
 ///
 /// 解析捆绑的 7-Zip 可执行文件的完整路径。
 ///
@@ -61,16 +64,16 @@ fn get_7z_resource_path() -> Result<String, String> {
 /// # 返回值
 ///
 /// * `Ok(PathBuf)` - 7-Zip 可执行文件的绝对路径。
-/// * `Err(String)` - 如果无法解析路径或可执行文件不存在，则返回错误消息。
-pub fn resolve_7z_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+/// * `Err(SoarZipError)` - 如果无法解析路径或可执行文件不存在，则返回 `SevenZipMissing`。
+pub fn resolve_7z_path(app_handle: &AppHandle) -> Result<PathBuf, SoarZipError> {
     let resource_path_str = get_7z_resource_path()?;
     let resource_dir = app_handle.path().resource_dir()
-        .map_err(|_| "Failed to get resource directory path".to_string())?; // Error getting resource dir
+        .map_err(|_| SoarZipError::SevenZipMissing("Failed to get resource directory path".to_string()))?; // Error getting resource dir
     let seven_zip_path_buf = resource_dir.join(resource_path_str);
 
     // Check if the resolved path actually exists
     if !seven_zip_path_buf.exists() {
-        return Err(format!("Bundled 7-Zip executable not found at expected path: {:?}", seven_zip_path_buf));
+        return Err(SoarZipError::SevenZipMissing(format!("Bundled 7-Zip executable not found at expected path: {:?}", seven_zip_path_buf)));
     }
     Ok(seven_zip_path_buf)
 }
@@ -86,7 +89,7 @@ pub fn resolve_7z_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
 /// # Returns
 ///
 /// * `Ok(Output)` - The process output if the command execution was initiated successfully (even if 7z returned an error).
-/// * `Err(String)` - An error message if the command failed to start.
+/// * `Err(SoarZipError)` - `SevenZipMissing` or `Io` if the command failed to start.
 ///
 /// 使用捆绑的可执行文件执行 7-Zip 命令。
 /// 处理特定平台的执行细节（如 Windows 上的 CREATE_NO_WINDOW）和输出解码。
@@ -99,30 +102,307 @@ pub fn resolve_7z_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
 /// # 返回值
 ///
 /// * `Ok(Output)` - 如果命令成功启动，则返回进程输出（即使 7z 返回错误）。
-/// * `Err(String)` - 如果命令启动失败，则返回错误消息。
-pub fn run_7z_command(seven_zip_path: &Path, args: &[String]) -> Result<Output, String> {
-    log_info(&format!("Executing 7-Zip command: {:?} {:?}", seven_zip_path, args));
+/// * `Err(SoarZipError)` - 如果命令启动失败，则返回 `SevenZipMissing` 或 `Io`。
+pub fn run_7z_command(seven_zip_path: &Path, args: &[String]) -> Result<Output, SoarZipError> {
+    run_7z_command_in_dir(seven_zip_path, args, None)
+}
 
-    #[cfg(target_os = "windows")]
-    let output_result = Command::new(seven_zip_path)
-        .args(args)
-        .creation_flags(0x08000000) // CREATE_NO_WINDOW flag to prevent console window popup
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output();
+/// Executes a 7-Zip command like [`run_7z_command`], optionally from a specific working directory.
+/// 7-Zip stores relative source paths as given, so running from a chosen root controls
+/// the folder structure written into new archives.
+///
+/// # Arguments
+///
+/// * `seven_zip_path` - The path to the 7-Zip executable.
+/// * `args` - A slice of string arguments for the 7-Zip command.
+/// * `working_dir` - The directory to run 7-Zip from, or `None` to inherit the current one.
+///
+/// # Returns
+///
+/// * `Ok(Output)` - The process output if the command execution was initiated successfully.
+/// * `Err(SoarZipError)` - `SevenZipMissing` or `Io` if the command failed to start.
+///
+/// 与 [`run_7z_command`] 相同地执行 7-Zip 命令，可选择指定工作目录。
+/// 7-Zip 会按给定形式保存相对源路径，因此从选定的根目录运行可以控制写入新压缩包的目录结构。
+///
+/// # 参数
+///
+/// * `seven_zip_path` - 7-Zip 可执行文件的路径。
+/// * `args` - 7-Zip 命令的字符串参数切片。
+/// * `working_dir` - 运行 7-Zip 的目录，为 `None` 时继承当前目录。
+///
+/// # 返回值
+///
+/// * `Ok(Output)` - 如果命令成功启动，则返回进程输出。
+/// * `Err(SoarZipError)` - 如果命令启动失败，则返回 `SevenZipMissing` 或 `Io`。
+pub fn run_7z_command_in_dir(seven_zip_path: &Path, args: &[String], working_dir: Option<&Path>) -> Result<Output, SoarZipError> {
+    log_info(&format!("Executing 7-Zip command: {:?} {:?} (cwd: {:?})", seven_zip_path, redact_args(args), working_dir));
 
-    #[cfg(not(target_os = "windows"))]
-    let output_result = Command::new(seven_zip_path)
-        .args(args)
+    let mut command = new_7z_command(seven_zip_path, args);
+    if let Some(dir) = working_dir {
+        command.current_dir(dir);
+    }
+
+    command
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
-        .output();
+        .output()
+        .map_err(spawn_error)
+}
+
+/// Converts a failure to start 7-Zip into a [`SoarZipError`].
+///
+/// 将 7-Zip 启动失败转换为 [`SoarZipError`]。
+pub(crate) fn spawn_error(e: std::io::Error) -> SoarZipError {
+    let error_msg = format!("Failed to execute bundled 7-Zip command: {}", e);
+    log_error(&error_msg);
+    if e.kind() == std::io::ErrorKind::NotFound {
+        SoarZipError::SevenZipMissing(error_msg)
+    } else {
+        SoarZipError::Io(error_msg)
+    }
+}
+
+/// Builds a `Command` for the bundled 7-Zip with platform-specific flags applied.
+///
+/// 构建应用了平台特定标志的捆绑 7-Zip `Command`。
+pub(crate) fn new_7z_command(seven_zip_path: &Path, args: &[String]) -> Command {
+    let mut command = Command::new(seven_zip_path);
+    command.args(args);
+    // 7-Zip asks for a missing password on stdin; a closed stdin makes it fail instead of hanging
+    // 7-Zip 会在 stdin 上询问缺失的密码；关闭 stdin 可使其失败而不是挂起
+    command.stdin(Stdio::null());
+    #[cfg(target_os = "windows")]
+    command.creation_flags(0x08000000); // CREATE_NO_WINDOW flag to prevent console window popup
+    command
+}
 
-    output_result.map_err(|e| {
-        let error_msg = format!("Failed to execute bundled 7-Zip command: {}", e);
+/// The output of a 7-Zip run that finished, possibly with warnings.
+/// 已结束的 7-Zip 运行的输出，可能带有警告。
+#[derive(Debug, Clone)]
+pub struct SevenZipOutput {
+    /// The decoded stdout.
+    pub stdout: String,
+    /// `Some(SoarZipError::Warning)` if 7-Zip exited with code 1: it finished, but skipped
+    /// something, e.g. a source file that was locked.
+    pub warning: Option<SoarZipError>,
+}
+
+/// Checks the exit status of a finished 7-Zip process. Exit code 1 means 7-Zip finished with
+/// warnings, so the output is returned together with the warning instead of failing.
+///
+/// # Arguments
+///
+/// * `output` - The process output returned by [`run_7z_command`].
+/// * `action` - A short name of the operation (e.g., "add"), used in the error message.
+///
+/// # Returns
+///
+/// * `Ok(SevenZipOutput)` - The decoded stdout if 7-Zip exited successfully or with warnings.
+/// * `Err(SoarZipError)` - The error classified from the exit code and stderr otherwise
+///   (see [`SoarZipError::from_7z_output`]).
+///
+/// 检查已结束的 7-Zip 进程的退出状态。退出码 1 表示 7-Zip 已完成但带有警告，
+/// 因此会将输出与警告一起返回，而不是视为失败。
+///
+/// # 参数
+///
+/// * `output` - [`run_7z_command`] 返回的进程输出。
+/// * `action` - 操作的简短名称（例如 "add"），用于错误消息。
+///
+/// # 返回值
+///
+/// * `Ok(SevenZipOutput)` - 如果 7-Zip 成功退出或带警告退出，则返回解码后的 stdout。
+/// * `Err(SoarZipError)` - 否则返回根据退出码和 stderr 分类的错误
+///   （参见 [`SoarZipError::from_7z_output`]）。
+pub fn check_7z_output(output: &Output, action: &str) -> Result<SevenZipOutput, SoarZipError> {
+    let mut warning = None;
+    if !output.status.success() {
+        let error = SoarZipError::from_7z_output(output, action);
+        if !matches!(error, SoarZipError::Warning(_)) {
+            log_error(&format!("Bundled 7-Zip {} command failed ({}): {}", action, error.code(), error));
+            return Err(error);
+        }
+        log_error(&format!("Bundled 7-Zip {} command finished with warnings: {}", action, error));
+        warning = Some(error);
+    }
+    Ok(SevenZipOutput { stdout: decode_7z_output(&output.stdout), warning })
+}
+
+/// Builds the `-p` switch for an optional password.
+///
+/// Without a password no switch is passed; since 7-Zip runs with a closed stdin, encrypted
+/// content then fails with a password error instead of waiting for input.
+///
+/// 为可选密码构建 `-p` 开关。
+///
+/// 没有密码时不传递开关；由于 7-Zip 在关闭的 stdin 下运行，加密内容会以密码错误失败，而不是等待输入。
+pub fn password_switch(password: Option<&str>) -> Option<String> {
+    password.filter(|p| !p.is_empty()).map(|p| format!("-p{}", p))
+}
+
+/// Returns a copy of 7-Zip arguments that is safe to log, with any password masked.
+/// Only switches before a `--` terminator are considered, since later arguments are file names.
+///
+/// 返回可安全记录日志的 7-Zip 参数副本，其中的密码已被屏蔽。
+/// 只考虑 `--` 终止符之前的开关，因为之后的参数是文件名。
+pub fn redact_args(args: &[String]) -> Vec<String> {
+    let mut switches_ended = false;
+    args.iter()
+        .map(|arg| {
+            if arg == "--" {
+                switches_ended = true;
+            }
+            if !switches_ended && arg.starts_with("-p") {
+                "-p***".to_string()
+            } else {
+                arg.clone()
+            }
+        })
+        .collect()
+}
+
+/// Creates a fresh, uniquely named directory under the system temp directory.
+///
+/// # Arguments
+///
+/// * `prefix` - A short label included in the directory name.
+///
+/// # Returns
+///
+/// * `Ok(PathBuf)` - The path of the created directory.
+/// * `Err(SoarZipError)` - `Io` if the directory could not be created.
+///
+/// 在系统临时目录下创建一个新的、名称唯一的目录。
+///
+/// # 参数
+///
+/// * `prefix` - 包含在目录名中的简短标签。
+///
+/// # 返回值
+///
+/// * `Ok(PathBuf)` - 已创建目录的路径。
+/// * `Err(SoarZipError)` - 如果无法创建目录，则返回 `Io`。
+pub fn create_temp_dir(prefix: &str) -> Result<PathBuf, SoarZipError> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!("soarzip-{}-{}-{}", prefix, std::process::id(), nanos));
+    std::fs::create_dir_all(&dir).map_err(|e| {
+        let error_msg = format!("Failed to create temporary directory '{}': {}", dir.display(), e);
         log_error(&error_msg);
-        error_msg
-    })
+        SoarZipError::Io(error_msg)
+    })?;
+    Ok(dir)
+}
+
+/// Writes entry names to a UTF-8 list file and passes its `@file` argument to `operation`.
+/// A list file keeps large selections clear of command line length limits.
+///
+/// 将条目名称写入 UTF-8 列表文件，并将其 `@file` 参数传给 `operation`。
+/// 列表文件可使大量选择不受命令行长度限制。
+pub fn run_with_list_file<T, F>(names: &[String], operation: F) -> Result<T, SoarZipError>
+where
+    F: FnOnce(&str) -> Result<T, SoarZipError>,
+{
+    let temp_dir = create_temp_dir("list")?;
+    let list_file = temp_dir.join("entries.txt");
+    let result = std::fs::write(&list_file, names.join("\n"))
+        .map_err(|e| SoarZipError::Io(format!("Failed to write list file '{}': {}", list_file.display(), e)))
+        .and_then(|_| operation(&format!("@{}", list_file.to_string_lossy())));
+    if let Err(e) = std::fs::remove_dir_all(&temp_dir) {
+        log_error(&format!("Failed to remove temporary directory '{}': {}", temp_dir.display(), e));
+    }
+    result
+}
+
+/// Lists the contents of an archive with `7z l -slt` and parses the result.
+///
+/// # Arguments
+///
+/// * `seven_zip_path` - The path to the 7-Zip executable.
+/// * `archive_path` - The path to the archive file.
+/// * `password` - Optional password for archives with encrypted headers.
+///
+/// # Returns
+///
+/// * `Ok(Vec<FileItem>)` - The items found in the archive.
+/// * `Err(SoarZipError)` - `PasswordRequired` if the listing is encrypted, or the 7-Zip error otherwise.
+///
+/// 使用 `7z l -slt` 列出压缩包内容并解析结果。
+///
+/// # 参数
+///
+/// * `seven_zip_path` - 7-Zip 可执行文件的路径。
+/// * `archive_path` - 压缩文件的路径。
+/// * `password` - 用于加密文件头压缩包的可选密码。
+///
+/// # 返回值
+///
+/// * `Ok(Vec<FileItem>)` - 在压缩包中找到的项目。
+/// * `Err(SoarZipError)` - 如果列表已加密则返回 `PasswordRequired`；否则返回 7-Zip 错误。
+pub fn list_archive_entries(seven_zip_path: &Path, archive_path: &str, password: Option<&str>) -> Result<Vec<FileItem>, SoarZipError> {
+    list_archive(seven_zip_path, archive_path, password).map(|opened| opened.entries)
+}
+
+/// Lists an archive like [`list_archive_entries`], but also returns the archive-level
+/// properties from the header block of the listing.
+///
+/// 与 [`list_archive_entries`] 一样列出压缩包内容，但同时返回列表头部块中的压缩包级别属性。
+pub fn list_archive(seven_zip_path: &Path, archive_path: &str, password: Option<&str>) -> Result<OpenedArchive, SoarZipError> {
+    let mut args = vec!["l".to_string(), "-slt".to_string()];
+    args.extend(password_switch(password));
+    args.push(archive_path.to_string());
+    let output = run_7z_command(seven_zip_path, &args)?;
+    let stdout_output = check_7z_output(&output, "list")?.stdout;
+    let entries = parse_7z_list_output(&stdout_output);
+    let info = parse_archive_info(&stdout_output, &decode_7z_output(&output.stderr), &entries);
+    Ok(OpenedArchive { info, entries })
+}
+
+/// Resolves a selection of archive paths into the listed items it covers.
+/// A selected folder covers itself and everything below it; an empty selection covers the whole archive.
+///
+/// # Arguments
+///
+/// * `entries` - The parsed listing of the archive.
+/// * `selection` - Selected entry paths inside the archive; folders may end with '/'.
+///
+/// # Returns
+///
+/// * `Vec<&FileItem>` - The covered items, each listed once.
+///
+/// 将压缩包路径选择解析为其覆盖的列表项目。
+/// 选中的文件夹覆盖其自身及其下所有内容；空选择覆盖整个压缩包。
+///
+/// # 参数
+///
+/// * `entries` - 压缩包的解析列表。
+/// * `selection` - 压缩包内选中的条目路径；文件夹可以以 '/' 结尾。
+///
+/// # 返回值
+///
+/// * `Vec<&FileItem>` - 被覆盖的项目，每个只列出一次。
+pub fn select_entries<'a>(entries: &'a [FileItem], selection: &[String]) -> Vec<&'a FileItem> {
+    if selection.is_empty() {
+        return entries.iter().collect();
+    }
+    let selected: Vec<(String, String)> = selection
+        .iter()
+        .map(|s| {
+            let bare = s.replace('\\', "/").trim_end_matches('/').to_string();
+            let prefix = format!("{}/", bare);
+            (bare, prefix)
+        })
+        .collect();
+    entries
+        .iter()
+        .filter(|item| {
+            let item_bare = item.name.trim_end_matches('/');
+            selected.iter().any(|(bare, prefix)| item_bare == bare || item.name.starts_with(prefix.as_str()))
+        })
+        .collect()
 }
 
 /// Decodes the output (stdout or stderr) of the 7-Zip process.
@@ -169,6 +449,17 @@ pub fn decode_7z_output(output_bytes: &[u8]) -> String {
 }
 
 
+/// Returns `None` for the empty values 7-Zip prints for properties an entry does not have.
+/// 对于条目不具备的属性，7-Zip 会输出空值，此时返回 `None`。
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
 /// Parses the detailed listing output (`l -slt`) from 7-Zip into a vector of FileItem structs.
 /// Also attempts to reconstruct the directory structure implicitly.
 ///
@@ -207,8 +498,14 @@ pub fn parse_7z_list_output(output_str: &str) -> Vec<FileItem> {
     let mut size: u64 = 0;
     let mut is_dir = false;
     let mut date = String::new();
+    // Optional metadata of the current item / 当前项目的可选元数据
+    let mut details = FileItem::default();
+
+    // Entries follow the "----------" separator; the block before it describes the archive itself
+    // 条目位于 "----------" 分隔线之后；其之前的块描述的是压缩包本身
+    let entries_section = output_str.split_once("\n----------").map_or(output_str, |(_, rest)| rest);
 
-    for line in output_str.lines() {
+    for line in entries_section.lines() {
         let line = line.trim();
 
         if line.starts_with("Path = ") {
@@ -228,6 +525,7 @@ pub fn parse_7z_list_output(output_str: &str) -> Vec<FileItem> {
             size = 0;
             is_dir = false;
             date = String::new();
+            details = FileItem::default();
 
         } else if line.starts_with("Size = ") {
             // Parse file size
@@ -244,6 +542,24 @@ pub fn parse_7z_list_output(output_str: &str) -> Vec<FileItem> {
         } else if line.starts_with("Modified = ") {
             // Store modification date
             date = line.trim_start_matches("Modified = ").to_string();
+        } else if let Some(value) = line.strip_prefix("Packed Size = ") {
+            details.packed_size = value.parse::<u64>().ok();
+        } else if let Some(value) = line.strip_prefix("CRC = ") {
+            details.crc = non_empty(value);
+        } else if let Some(value) = line.strip_prefix("Method = ") {
+            details.method = non_empty(value);
+        } else if let Some(value) = line.strip_prefix("Encrypted = ") {
+            details.encrypted = value == "+";
+        } else if let Some(value) = line.strip_prefix("Attributes = ") {
+            details.attributes = non_empty(value);
+        } else if let Some(value) = line.strip_prefix("Created = ") {
+            details.created_date = non_empty(value);
+        } else if let Some(value) = line.strip_prefix("Accessed = ") {
+            details.accessed_date = non_empty(value);
+        } else if let Some(value) = line.strip_prefix("Comment = ") {
+            details.comment = non_empty(value);
+        } else if let Some(value) = line.strip_prefix("Symbolic Link = ") {
+            details.symlink_target = non_empty(value);
         } else if line.is_empty() && !path_str.is_empty() {
             // An empty line signifies the end of a properties block for an item
             // Determine file type based on extension or if it's a directory
@@ -288,6 +604,7 @@ pub fn parse_7z_list_output(output_str: &str) -> Vec<FileItem> {
                 size,
                 modified_date: date.clone(),
                 type_name,
+                ..std::mem::take(&mut details)
             };
 
             // Store it to be potentially added in the next "Path = " line or at the end
@@ -337,6 +654,7 @@ pub fn parse_7z_list_output(output_str: &str) -> Vec<FileItem> {
                                 size: 0, // Directories have size 0 in this context
                                 modified_date: "".to_string(), // No date info available from parents
                                 type_name: "Folder".to_string(),
+                                ..Default::default()
                             });
                         }
                         current_parent = parent.parent(); // Move to the next parent
@@ -388,4 +706,98 @@ pub fn parse_7z_list_output(output_str: &str) -> Vec<FileItem> {
     // }
 
     files
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_7z_list_output;
+
+    const LISTING: &str = "\
+7-Zip 23.01 (x64) : Copyright (c) 1999-2023 Igor Pavlov : 2023-06-20
+
+Scanning the drive for archives:
+1 file, 1000 bytes (1 KiB)
+
+Listing archive: sample.7z
+
+--
+Path = sample.7z
+Type = 7z
+Physical Size = 1000
+Solid = +
+
+----------
+Path = docs
+Size = 0
+Packed Size = 0
+Modified = 2024-01-02 03:04:05
+Attributes = D
+Folder = +
+
+Path = docs\\a.txt
+Size = 3000
+Packed Size = 800
+Modified = 2024-01-02 03:04:05
+Attributes = A
+CRC = 0A1B2C3D
+Encrypted = +
+Method = LZMA2:24 7zAES
+Folder = -
+
+Path = bin/tool.sh
+Size = 120
+Packed Size = 
+Modified = 2024-01-03 10:00:00
+CRC = 
+Symbolic Link = 
+Folder = -
+
+";
+
+    fn names(listing: &str) -> Vec<String> {
+        parse_7z_list_output(listing).into_iter().map(|item| item.name).collect()
+    }
+
+    #[test]
+    fn skips_the_archive_header_block() {
+        assert!(!names(LISTING).contains(&"sample.7z".to_string()));
+    }
+
+    #[test]
+    fn lists_folders_first_and_adds_missing_parents() {
+        assert_eq!(names(LISTING), vec!["bin/", "docs/", "bin/tool.sh", "docs/a.txt"]);
+    }
+
+    #[test]
+    fn parses_entry_properties() {
+        let entries = parse_7z_list_output(LISTING);
+        let file = entries.iter().find(|item| item.name == "docs/a.txt").unwrap();
+        assert!(!file.is_dir);
+        assert_eq!(file.size, 3000);
+        assert_eq!(file.packed_size, Some(800));
+        assert_eq!(file.modified_date, "2024-01-02 03:04:05");
+        assert_eq!(file.crc.as_deref(), Some("0A1B2C3D"));
+        assert_eq!(file.method.as_deref(), Some("LZMA2:24 7zAES"));
+        assert!(file.encrypted);
+
+        // Empty values are left unset / 空值保持未设置
+        let script = entries.iter().find(|item| item.name == "bin/tool.sh").unwrap();
+        assert_eq!(script.packed_size, None);
+        assert_eq!(script.crc, None);
+        assert_eq!(script.symlink_target, None);
+    }
+
+    #[test]
+    fn reads_a_listing_without_header_block() {
+        let listing = "Path = readme.md\nSize = 10\nFolder = -\n\n";
+        assert_eq!(names(listing), vec!["readme.md"]);
+    }
+
+    #[test]
+    fn keeps_duplicate_paths_once() {
+        let listing = "----------\nPath = a.txt\nSize = 1\n\nPath = a.txt\nSize = 2\n\n";
+        let entries = parse_7z_list_output(listing);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].size, 1);
+    }
+}
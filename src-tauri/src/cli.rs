@@ -0,0 +1,248 @@
+//! Headless command-line mode: `soar-zip l|x|a|t ...` runs one archive operation without
+//! showing a window, prints the result and exits with a meaningful code.
+//!
+//! 无界面命令行模式：`soar-zip l|x|a|t ...` 在不显示窗口的情况下运行一个压缩包操作，
+//! 输出结果并以有意义的退出码退出。
+
+use std::path::Path;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+use tauri_plugin_cli::SubcommandMatches;
+
+use super::archive_create::{ArchiveFormat, EncryptionOptions};
+use super::archive_extract::{ExtractLayout, ExtractOptions, OverwritePolicy, PathMode, UnsafePathPolicy};
+use super::archive_test::TestVerdict;
+use super::error::SoarZipError;
+use super::operations;
+
+/// Exit code for success / 成功时的退出码
+const EXIT_OK: i32 = 0;
+/// Exit code when `x` stopped because files already exist, matching 7-Zip's warning code
+/// `x` 因文件已存在而停止时的退出码，与 7-Zip 的警告码一致
+const EXIT_CONFLICTS: i32 = 1;
+/// Exit code for an archive `a` wrote with warnings, matching 7-Zip's warning code
+/// `a` 带警告写入压缩包时的退出码，与 7-Zip 的警告码一致
+const EXIT_WARNING: i32 = 1;
+/// Exit code for an archive that failed its integrity test, matching 7-Zip's fatal error code
+/// 压缩包未通过完整性测试时的退出码，与 7-Zip 的致命错误码一致
+const EXIT_DAMAGED: i32 = 2;
+
+/// Runs a CLI subcommand and returns the process exit code.
+///
+/// Supported subcommands mirror 7-Zip's letters: `l` (list), `x` (extract), `a` (create)
+/// and `t` (test). Each reuses the same operation as the corresponding Tauri command.
+/// With `--json`, results and errors are printed as JSON; errors always go to stderr.
+///
+/// # Arguments
+///
+/// * `app_handle` - The Tauri application handle, used to resolve the bundled 7-Zip.
+/// * `subcommand` - The parsed subcommand.
+///
+/// # Returns
+///
+/// * `0` on success, `2` if `t` found a damaged archive, or the error's
+///   [`SoarZipError::exit_code`] otherwise.
+///
+/// 运行 CLI 子命令并返回进程退出码。
+///
+/// 支持的子命令与 7-Zip 的字母一致：`l`（列出）、`x`（解压）、`a`（创建）和 `t`（测试）。
+/// 每个子命令都复用对应 Tauri 命令的同一操作。
+/// 使用 `--json` 时，结果和错误以 JSON 输出；错误始终输出到 stderr。
+///
+/// # 参数
+///
+/// * `app_handle` - Tauri 应用程序句柄，用于解析捆绑的 7-Zip。
+/// * `subcommand` - 已解析的子命令。
+///
+/// # 返回值
+///
+/// * 成功时为 `0`；如果 `t` 发现压缩包已损坏则为 `2`；否则为错误的 [`SoarZipError::exit_code`]。
+pub fn run_subcommand(app_handle: &AppHandle, subcommand: &SubcommandMatches) -> i32 {
+    let args = CliArgs { subcommand };
+    let json = args.flag("json");
+    let result = match subcommand.name.as_str() {
+        "l" => list(app_handle, &args),
+        "x" => extract(app_handle, &args),
+        "a" => create(app_handle, &args),
+        "t" => test(app_handle, &args),
+        other => Err(SoarZipError::CommandLine(format!("Unknown subcommand: {}", other))),
+    };
+    match result {
+        Ok(code) => code,
+        Err(error) => {
+            if json {
+                eprintln!("{}", to_json(&error));
+            } else {
+                eprintln!("Error [{}]: {}", error.code(), error);
+            }
+            error.exit_code()
+        }
+    }
+}
+
+/// `soar-zip l <archive> [--json] [-p <password>]`
+fn list(app_handle: &AppHandle, args: &CliArgs) -> Result<i32, SoarZipError> {
+    let opened = operations::open_archive(app_handle, args.required("archive")?, args.value("password"))?;
+    if args.flag("json") {
+        println!("{}", to_json(&opened));
+        return Ok(EXIT_OK);
+    }
+
+    println!("Type: {}", opened.info.archive_type);
+    println!("{:>14}  {:<19}  Name", "Size", "Modified");
+    let mut entries: Vec<_> = opened.entries.iter().collect();
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    for item in &entries {
+        let size = if item.is_dir { "<DIR>".to_string() } else { item.size.to_string() };
+        println!("{:>14}  {:<19}  {}", size, item.modified_date, item.name);
+    }
+    let files = entries.iter().filter(|item| !item.is_dir).count();
+    println!("{} files, {} folders", files, entries.len() - files);
+    Ok(EXIT_OK)
+}
+
+/// `soar-zip x <archive> [files...] [-o <dir>] [-p <password>] [--unsafe-paths <policy>] [--overwrite <policy>] [--smart] [-e | --relative-to <folder>] [--json]`
+fn extract(app_handle: &AppHandle, args: &CliArgs) -> Result<i32, SoarZipError> {
+    let archive_path = args.required("archive")?;
+    let output_directory = args.value("output").unwrap_or_else(|| ".".to_string());
+    let mut options = ExtractOptions::default();
+    if let Some(policy) = args.value("unsafe-paths") {
+        options.unsafe_paths = serde_json::from_value::<UnsafePathPolicy>(Value::String(policy.clone()))
+            .map_err(|_| SoarZipError::CommandLine(format!("Unknown unsafe path policy: {}", policy)))?;
+    }
+    if args.flag("smart") {
+        options.layout = ExtractLayout::Smart;
+    }
+    if args.flag("flatten") {
+        options.path_mode = PathMode::Flatten;
+    } else if let Some(base_path) = args.value("relative-to") {
+        options.path_mode = PathMode::Relative;
+        options.base_path = base_path;
+    }
+    if let Some(policy) = args.value("overwrite") {
+        options.overwrite = serde_json::from_value::<OverwritePolicy>(Value::String(policy.clone()))
+            .map_err(|_| SoarZipError::CommandLine(format!("Unknown overwrite policy: {}", policy)))?;
+    }
+    let report = operations::extract_files(
+        app_handle,
+        archive_path.clone(),
+        args.values("files"),
+        output_directory,
+        args.value("password"),
+        options,
+        None,
+    )?;
+    if args.flag("json") {
+        println!("{}", to_json(&report));
+    } else if !report.conflicts.is_empty() {
+        for path in &report.conflicts {
+            println!("EXISTS     {}", path);
+        }
+        eprintln!("Nothing was extracted: {} files already exist. Pass --overwrite to choose what to do.", report.conflicts.len());
+    } else {
+        for entry in &report.blocked_entries {
+            match &entry.sanitized_path {
+                Some(path) => println!("SANITIZED  {} -> {}", entry.path, path),
+                None => println!("SKIPPED    {}", entry.path),
+            }
+        }
+        println!("Extracted {} to {}", archive_path, report.output_directory);
+    }
+    Ok(if report.conflicts.is_empty() { EXIT_OK } else { EXIT_CONFLICTS })
+}
+
+/// `soar-zip a <archive> <sources...> [--format <fmt>] [-p <password>] [--encrypt-names] [--json]`
+fn create(app_handle: &AppHandle, args: &CliArgs) -> Result<i32, SoarZipError> {
+    let archive_path = args.required("archive")?;
+    let sources = args.values("sources");
+    if sources.is_empty() {
+        return Err(SoarZipError::CommandLine("No source files were given.".to_string()));
+    }
+    let format = match args.value("format") {
+        Some(name) => serde_json::from_value::<ArchiveFormat>(Value::String(name.clone()))
+            .map_err(|_| SoarZipError::CommandLine(format!("Unknown archive format: {}", name)))?,
+        None => ArchiveFormat::from_path(Path::new(&archive_path)).ok_or_else(|| {
+            SoarZipError::CommandLine(format!("Cannot tell the format from '{}'; pass --format.", archive_path))
+        })?,
+    };
+    if args.flag("encrypt-names") && args.value("password").is_none() {
+        return Err(SoarZipError::InvalidInput("--encrypt-names requires a password (-p).".to_string()));
+    }
+    let encryption = args.value("password").map(|password| EncryptionOptions {
+        password,
+        encrypt_file_names: args.flag("encrypt-names"),
+        method: Default::default(),
+    });
+
+    let created = operations::create_archive(app_handle, sources, archive_path, format, None, encryption, None)?;
+    if args.flag("json") {
+        println!("{}", to_json(&created));
+    } else {
+        println!("Created {} ({} bytes)", created.archive_path, created.archive_size);
+    }
+    for warning in &created.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    Ok(if created.warnings.is_empty() { EXIT_OK } else { EXIT_WARNING })
+}
+
+/// `soar-zip t <archive> [-p <password>] [--json]`
+fn test(app_handle: &AppHandle, args: &CliArgs) -> Result<i32, SoarZipError> {
+    let report = operations::test_archive(app_handle, args.required("archive")?, args.value("password"), None)?;
+    if args.flag("json") {
+        println!("{}", to_json(&report));
+    } else {
+        for failed in &report.failed_entries {
+            println!("FAILED  {}  ({})", failed.path, failed.message);
+        }
+        for error in &report.archive_errors {
+            println!("ERROR   {}", error);
+        }
+        println!(
+            "{} entries: {} passed, {} failed. Verdict: {}",
+            report.total_entries,
+            report.passed_entries,
+            report.failed_entries.len(),
+            to_json(&report.verdict).trim_matches('"')
+        );
+    }
+    Ok(if report.verdict == TestVerdict::Ok { EXIT_OK } else { EXIT_DAMAGED })
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_else(|e| format!("{{\"error\":\"Failed to serialize output: {}\"}}", e))
+}
+
+/// Typed access to the argument values of a subcommand.
+/// 对子命令参数值的类型化访问。
+struct CliArgs<'a> {
+    subcommand: &'a SubcommandMatches,
+}
+
+impl CliArgs<'_> {
+    fn raw(&self, name: &str) -> Option<&Value> {
+        self.subcommand.matches.args.get(name).map(|arg| &arg.value)
+    }
+
+    fn value(&self, name: &str) -> Option<String> {
+        self.raw(name).and_then(Value::as_str).map(str::to_string)
+    }
+
+    fn required(&self, name: &str) -> Result<String, SoarZipError> {
+        self.value(name)
+            .ok_or_else(|| SoarZipError::CommandLine(format!("Missing required argument: {}", name)))
+    }
+
+    fn values(&self, name: &str) -> Vec<String> {
+        match self.raw(name) {
+            Some(Value::Array(values)) => values.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+            Some(Value::String(value)) => vec![value.clone()],
+            _ => Vec::new(),
+        }
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.raw(name).and_then(Value::as_bool).unwrap_or(false)
+    }
+}
@@ -1,14 +1,23 @@
 //! Tauri commands exposed to the frontend.
 //! 暴露给前端的 Tauri 命令。
 
-use tauri::{Window, AppHandle}; // Add AppHandle for commands needing it
+use tauri::{Window, AppHandle, State}; // Add AppHandle for commands needing it
 use rfd::FileDialog;
-use std::path::Path;
 
 // Import struct and utils from sibling modules
 use super::file_item::FileItem;
+use super::archive_info::OpenedArchive;
 use super::logging::{log_info, log_error};
-use super::archive_utils::{resolve_7z_path, run_7z_command, decode_7z_output, parse_7z_list_output};
+use super::archive_create::{ArchiveFormat, CreatedArchive, EncryptionOptions};
+use super::archive_extract::{ExtractOptions, ExtractionReport};
+use super::archive_edit::AddConflictPolicy;
+use super::archive_test::ArchiveTestReport;
+use super::archive_preview::EntryPreview;
+use super::archive_search::{SearchOptions, SearchReport};
+use super::jobs::JobRegistry;
+use super::temp_files::TempFileRegistry;
+use super::operations;
+use super::error::SoarZipError;
 
 // --- Window Commands --- 
 
@@ -185,71 +194,73 @@ pub fn select_destination_folder() -> Option<String> {
 }
 
 // --- Archive Operation Commands --- 
+//
+// Archive commands are async and hand the 7-Zip work to a blocking worker pool, so several
+// listings and extractions can run at once while window commands stay responsive.
+// 压缩包命令是异步的，并将 7-Zip 工作交给阻塞工作线程池，因此多个列表和解压操作可以同时运行，
+// 而窗口命令保持响应。
+//
+// Failures are returned as `SoarZipError`, which reaches the frontend as `{ code, message }`.
+// 失败以 `SoarZipError` 返回，前端收到的形式为 `{ code, message }`。
+
+/// Runs a blocking archive operation on Tauri's blocking worker pool and awaits its result.
+///
+/// 在 Tauri 的阻塞工作线程池中运行阻塞的压缩包操作并等待其结果。
+async fn run_blocking<T, F>(task: F) -> Result<T, SoarZipError>
+where
+    F: FnOnce() -> Result<T, SoarZipError> + Send + 'static,
+    T: Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(task)
+        .await
+        .map_err(|e| {
+            let error_msg = format!("Background archive task failed: {}", e);
+            log_error(&error_msg);
+            SoarZipError::Io(error_msg)
+        })?
+}
 
-/// Opens an archive file and lists its contents using the bundled 7-Zip.
+/// Opens an archive file and lists its contents and properties using the bundled 7-Zip.
+///
+/// An archive inside another archive is opened through a virtual path such as
+/// `outer.7z!/inner/data.zip`; it is extracted to the session's temporary directory and can then
+/// be extracted from, tested and previewed like any other archive, but not modified.
 ///
 /// # Arguments
 ///
 /// * `app_handle` - The Tauri application handle (injected automatically).
-/// * `archive_path` - The path to the archive file.
+/// * `archive_path` - The path to the archive file, or a virtual path to a nested archive.
+/// * `password` - Optional password for archives with encrypted file names. It is never logged.
 ///
 /// # Returns
 ///
-/// * `Ok(Vec<FileItem>)` - A vector of items found in the archive.
-/// * `Err(String)` - An error message if opening or parsing fails.
+/// * `Ok(OpenedArchive)` - The archive properties (type, sizes, method, solid, volumes, comment,
+///   compression ratio, warnings) and the items found in the archive.
+/// * `Err(SoarZipError)` - `PASSWORD_REQUIRED` if the password is missing or wrong, or another error code if opening or parsing fails.
 ///
-/// 使用捆绑的 7-Zip 打开压缩文件并列出其内容。
+/// 使用捆绑的 7-Zip 打开压缩文件并列出其内容和属性。
+///
+/// 另一个压缩包内的压缩包通过 `outer.7z!/inner/data.zip` 这样的虚拟路径打开；它会被解压到会话临时目录，
+/// 之后可以像其他压缩包一样解压、测试和预览，但不能修改。
 ///
 /// # 参数
 ///
 /// * `app_handle` - Tauri 应用程序句柄（自动注入）。
-/// * `archive_path` - 压缩文件的路径。
+/// * `archive_path` - 压缩文件的路径，或指向嵌套压缩包的虚拟路径。
+/// * `password` - 用于文件名已加密的压缩包的可选密码。该密码绝不会被记录到日志。
 ///
 /// # 返回值
 ///
-/// * `Ok(Vec<FileItem>)` - 在压缩包中找到的项目向量。
-/// * `Err(String)` - 如果打开或解析失败，则返回错误消息。
+/// * `Ok(OpenedArchive)` - 压缩包属性（类型、大小、方法、固实、分卷、注释、压缩率、警告）
+///   以及在压缩包中找到的项目。
+/// * `Err(SoarZipError)` - 如果密码缺失或错误则返回 `PASSWORD_REQUIRED`；如果打开或解析失败，则返回其他错误码。
 #[tauri::command]
-pub fn open_archive(app_handle: AppHandle, archive_path: String) -> Result<Vec<FileItem>, String> {
-    log_info(&format!("Attempting to open archive: {}", archive_path));
-
-    // Check if the archive file exists
-    if !Path::new(&archive_path).exists() {
-        let error_msg = format!("Archive file not found: {}", archive_path);
-        log_error(&error_msg);
-        return Err(error_msg);
-    }
-
-    // Resolve the path to the bundled 7-Zip executable
-    let seven_zip_path = resolve_7z_path(&app_handle)?;
-    log_info(&format!("Using bundled 7-Zip at: {:?}", seven_zip_path));
-
-    // Prepare arguments for 7-Zip list command (detailed list)
-    let args = vec!["l".to_string(), "-slt".to_string(), archive_path.clone()];
-
-    // Execute the 7-Zip command
-    let output = run_7z_command(&seven_zip_path, &args)?;
-
-    // Check if the 7-Zip command executed successfully (exit code 0)
-    if !output.status.success() {
-        let stderr_output = decode_7z_output(&output.stderr);
-        let error_msg = format!(
-            "Bundled 7-Zip list command failed with exit code: {}. Error: {}",
-            output.status.code().unwrap_or(-1),
-            stderr_output.trim()
-        );
-        log_error(&error_msg);
-        return Err(error_msg);
-    }
-
-    // Decode the stdout
-    let stdout_output = decode_7z_output(&output.stdout);
-
-    // Parse the decoded output
-    let files = parse_7z_list_output(&stdout_output);
-
-    log_info(&format!("Successfully listed archive: {}", archive_path));
-    Ok(files)
+pub async fn open_archive(
+    app_handle: AppHandle,
+    archive_path: String,
+    password: Option<String>,
+) -> Result<OpenedArchive, SoarZipError> {
+    run_blocking(move || operations::open_archive(&app_handle, archive_path, password)).await
 }
 
 
@@ -262,11 +273,23 @@ pub fn open_archive(app_handle: AppHandle, archive_path: String) -> Result<Vec<F
 /// * `archive_path` - The path to the archive file.
 /// * `files_to_extract` - A vector of relative paths within the archive to extract. If empty, extracts all.
 /// * `output_directory` - The destination directory where files will be extracted.
+/// * `password` - Optional password for encrypted archives. It is never logged.
+/// * `options` - Optional extraction options. `unsafe_paths` decides what happens to entries that would
+///   be written outside the output directory: "refuse" (default), "skip" or "sanitize". `overwrite` decides
+///   what happens to existing files: "ask" (default), "overwrite_all", "skip_existing", "rename_extracted"
+///   or "rename_existing". `layout` "smart" treats `output_directory` as "extract here" and adds a subfolder
+///   named after the archive only when there are several top-level entries. `path_mode` "relative" strips
+///   `base_path` (e.g., the browsed folder) from the front of every path, and "flatten" puts all selected
+///   files directly into the output directory, renaming files with the same name to `name_N.ext`.
+/// * `job_id` - Optional id for this operation, usable with `cancel_operation` while it runs.
 ///
 /// # Returns
 ///
-/// * `Ok(())` - If the extraction was successful.
-/// * `Err(String)` - An error message if extraction fails.
+/// * `Ok(ExtractionReport)` - If the extraction was successful: the directory actually used and the entries
+///   that were skipped or sanitized.
+///   With "ask", a report with non-empty `conflicts` means nothing was written yet.
+/// * `Err(SoarZipError)` - `OPERATION_CANCELLED` if the extraction was cancelled, `PASSWORD_REQUIRED` if the
+///   password is missing or wrong, `UNSAFE_ENTRY_PATHS` if unsafe entries were refused, or another error code if it fails.
 ///
 /// 将指定文件或所有文件从压缩包解压到目标目录。
 /// 使用捆绑的 7-Zip 可执行文件。
@@ -277,104 +300,494 @@ pub fn open_archive(app_handle: AppHandle, archive_path: String) -> Result<Vec<F
 /// * `archive_path` - 压缩文件的路径。
 /// * `files_to_extract` - 要解压的压缩包内相对路径的向量。如果为空，则解压所有文件。
 /// * `output_directory` - 文件将被解压到的目标目录。
+/// * `password` - 用于加密压缩包的可选密码。该密码绝不会被记录到日志。
+/// * `options` - 可选的解压选项。`unsafe_paths` 决定如何处理会被写到输出目录之外的条目：
+///   "refuse"（默认）、"skip" 或 "sanitize"。`overwrite` 决定如何处理已存在的文件：
+///   "ask"（默认）、"overwrite_all"、"skip_existing"、"rename_extracted" 或 "rename_existing"。
+///   `layout` 为 "smart" 时将 `output_directory` 视为"解压到此处"，仅在存在多个顶层条目时才添加以压缩包命名的子文件夹。
+///   `path_mode` 为 "relative" 时从每个路径的开头去掉 `base_path`（例如正在浏览的文件夹），
+///   为 "flatten" 时将所有所选文件直接放入输出目录，并将同名文件重命名为 `name_N.ext`。
+/// * `job_id` - 此操作的可选 id，运行期间可用于 `cancel_operation`。
 ///
 /// # 返回值
 ///
-/// * `Ok(())` - 如果解压成功。
-/// * `Err(String)` - 如果解压失败，则返回错误消息。
+/// * `Ok(ExtractionReport)` - 如果解压成功：实际使用的目录以及被跳过或清理的条目。
+///   使用 "ask" 时，`conflicts` 不为空的报告表示尚未写入任何内容。
+/// * `Err(SoarZipError)` - 如果解压被取消则返回 `OPERATION_CANCELLED`；如果密码缺失或错误则返回
+///   `PASSWORD_REQUIRED`；如果不安全的条目被拒绝则返回 `UNSAFE_ENTRY_PATHS`；如果解压失败，则返回其他错误码。
 #[tauri::command]
-pub fn extract_files(
+pub async fn extract_files(
     app_handle: AppHandle,
     archive_path: String,
     files_to_extract: Vec<String>, // List of relative paths inside the archive
     output_directory: String,
-) -> Result<(), String> {
-    log_info(&format!(
-        "Starting extraction to: {}, Archive: {}",
-        output_directory, archive_path
-    ));
-    if !files_to_extract.is_empty() {
-        log_info(&format!("Files/folders to extract: {:?}", files_to_extract));
-    } else {
-        log_info("Extracting all contents.");
-    }
+    password: Option<String>,
+    options: Option<ExtractOptions>,
+    job_id: Option<String>,
+) -> Result<ExtractionReport, SoarZipError> {
+    run_blocking(move || {
+        let options = options.unwrap_or_default();
+        operations::extract_files(&app_handle, archive_path, files_to_extract, output_directory, password, options, job_id)
+    })
+    .await
+}
 
-    // Check if archive file exists
-    if !Path::new(&archive_path).exists() {
-        let error_msg = format!("Archive file not found: {}", archive_path);
-        log_error(&error_msg);
-        return Err(error_msg);
-    }
+/// Creates a new archive from files and folders on disk using the bundled 7-Zip.
+///
+/// # Arguments
+///
+/// * `app_handle` - The Tauri application handle (injected automatically).
+/// * `source_paths` - Files and folders to compress.
+/// * `archive_path` - The path of the archive to create. It must not exist yet.
+/// * `format` - The archive format: "zip", "7z", "tar", "tar.gz" or "tar.xz".
+/// * `relative_root` - Optional directory that entry paths inside the archive are relative to.
+///   If omitted, each source is stored under its own name.
+/// * `encryption` - Optional password protection for 7z and zip: the password (never logged),
+///   whether to encrypt file names (7z only), and the zip cipher. AES-256 is used unless
+///   "zip_crypto" is requested explicitly. The result is verified by test-opening it.
+/// * `job_id` - Optional id for this operation, usable with `cancel_operation` while it runs.
+///
+/// # Returns
+///
+/// * `Ok(CreatedArchive)` - The path, format, size and encryption state of the new archive.
+/// * `Err(SoarZipError)` - `OPERATION_CANCELLED` if creation was cancelled, or another error code if it fails.
+///
+/// 使用捆绑的 7-Zip 从磁盘上的文件和文件夹创建新的压缩包。
+///
+/// # 参数
+///
+/// * `app_handle` - Tauri 应用程序句柄（自动注入）。
+/// * `source_paths` - 要压缩的文件和文件夹。
+/// * `archive_path` - 要创建的压缩包路径，必须尚不存在。
+/// * `format` - 压缩包格式："zip"、"7z"、"tar"、"tar.gz" 或 "tar.xz"。
+/// * `relative_root` - 可选的目录，压缩包内的条目路径将相对于该目录。
+///   如果省略，每个源以其自身名称保存。
+/// * `encryption` - 可选的 7z 和 zip 密码保护：密码（绝不记录到日志）、是否加密文件名（仅限 7z）
+///   以及 zip 加密算法。除非明确请求 "zip_crypto"，否则使用 AES-256。结果会通过试打开进行校验。
+/// * `job_id` - 此操作的可选 id，运行期间可用于 `cancel_operation`。
+///
+/// # 返回值
+///
+/// * `Ok(CreatedArchive)` - 新压缩包的路径、格式、大小和加密状态。
+/// * `Err(SoarZipError)` - 如果创建被取消则返回 `OPERATION_CANCELLED`；如果创建失败，则返回其他错误码。
+#[tauri::command]
+pub async fn create_archive(
+    app_handle: AppHandle,
+    source_paths: Vec<String>,
+    archive_path: String,
+    format: ArchiveFormat,
+    relative_root: Option<String>,
+    encryption: Option<EncryptionOptions>,
+    job_id: Option<String>,
+) -> Result<CreatedArchive, SoarZipError> {
+    run_blocking(move || {
+        operations::create_archive(&app_handle, source_paths, archive_path, format, relative_root, encryption, job_id)
+    })
+    .await
+}
 
-    // Check if output directory exists, create if not
-    let output_path = Path::new(&output_directory);
-    if !output_path.exists() {
-        log_info(&format!("Output directory does not exist, attempting to create: {}", output_directory));
-        if let Err(e) = std::fs::create_dir_all(output_path) {
-            let error_msg = format!("Failed to create output directory '{}': {}", output_directory, e);
-            log_error(&error_msg);
-            return Err(error_msg);
-        }
-        log_info(&format!("Successfully created output directory: {}", output_directory));
-    } else if !output_path.is_dir() {
-         // Ensure the output path is actually a directory
-         let error_msg = format!("Output path exists but is not a directory: {}", output_directory);
-         log_error(&error_msg);
-         return Err(error_msg);
-    }
+/// Adds files and folders from disk to an existing archive and returns the refreshed listing.
+///
+/// # Arguments
+///
+/// * `app_handle` - The Tauri application handle (injected automatically).
+/// * `archive_path` - The path to the archive file to update.
+/// * `target_dir` - The folder inside the archive to add the files to ("" for the root).
+/// * `source_paths` - Files and folders on disk to add.
+/// * `conflict_policy` - What to do when an entry already exists: "replace", "skip" or "keep_both".
+///
+/// # Returns
+///
+/// * `Ok(Vec<FileItem>)` - The updated list of items in the archive.
+/// * `Err(SoarZipError)` - The error if updating the archive fails.
+///
+/// 将磁盘上的文件和文件夹添加到现有压缩包，并返回刷新后的列表。
+///
+/// # 参数
+///
+/// * `app_handle` - Tauri 应用程序句柄（自动注入）。
+/// * `archive_path` - 要更新的压缩文件路径。
+/// * `target_dir` - 压缩包内要添加到的文件夹（"" 表示根目录）。
+/// * `source_paths` - 要添加的磁盘文件和文件夹。
+/// * `conflict_policy` - 条目已存在时的处理方式："replace"、"skip" 或 "keep_both"。
+///
+/// # 返回值
+///
+/// * `Ok(Vec<FileItem>)` - 压缩包中更新后的项目列表。
+/// * `Err(SoarZipError)` - 如果更新压缩包失败，则返回相应错误。
+#[tauri::command]
+pub async fn add_to_archive(
+    app_handle: AppHandle,
+    archive_path: String,
+    target_dir: String,
+    source_paths: Vec<String>,
+    conflict_policy: AddConflictPolicy,
+) -> Result<Vec<FileItem>, SoarZipError> {
+    run_blocking(move || {
+        operations::add_to_archive(&app_handle, archive_path, target_dir, source_paths, conflict_policy)
+    })
+    .await
+}
 
-    // Resolve 7-Zip path
-    let seven_zip_path = resolve_7z_path(&app_handle)?;
-    log_info(&format!("Using bundled 7-Zip for extraction: {:?}", seven_zip_path));
+/// Deletes files and folders from an archive and returns the refreshed listing.
+/// Folders are deleted recursively. The archive is rewritten through a temporary copy,
+/// so it is only replaced once 7-Zip has finished successfully.
+///
+/// # Arguments
+///
+/// * `app_handle` - The Tauri application handle (injected automatically).
+/// * `archive_path` - The path to the archive file to update.
+/// * `files` - Entry paths inside the archive to delete; folders may end with '/'.
+///
+/// # Returns
+///
+/// * `Ok(Vec<FileItem>)` - The updated list of items in the archive.
+/// * `Err(SoarZipError)` - The error if deletion fails.
+///
+/// 从压缩包中删除文件和文件夹，并返回刷新后的列表。
+/// 文件夹会被递归删除。压缩包通过临时副本重写，只有在 7-Zip 成功完成后才会被替换。
+///
+/// # 参数
+///
+/// * `app_handle` - Tauri 应用程序句柄（自动注入）。
+/// * `archive_path` - 要更新的压缩文件路径。
+/// * `files` - 要删除的压缩包内条目路径；文件夹可以以 '/' 结尾。
+///
+/// # 返回值
+///
+/// * `Ok(Vec<FileItem>)` - 压缩包中更新后的项目列表。
+/// * `Err(SoarZipError)` - 如果删除失败，则返回相应错误。
+#[tauri::command]
+pub async fn delete_files_in_archive(
+    app_handle: AppHandle,
+    archive_path: String,
+    files: Vec<String>,
+) -> Result<Vec<FileItem>, SoarZipError> {
+    run_blocking(move || operations::delete_files_in_archive(&app_handle, archive_path, files)).await
+}
 
-    // Build 7-Zip command arguments
-    // Base command: 7z x <archive_path> -o<output_directory> [files_to_extract...] -aoa
-    // 'x': Extract files with full paths
-    // '-o': Specify output directory (no space after -o)
-    // 'files_to_extract...': Optional list of files/dirs to extract (relative paths)
-    // '-aoa': Overwrite All existing files without prompt.
-    let mut args = vec![
-        "x".to_string(),                // Extract command
-        archive_path.clone(),       // Archive path
-        format!("-o{}", output_directory), // Output directory (no space!)
-        "-aoa".to_string(),             // Overwrite mode: Overwrite All files Always
-    ];
+/// Renames or moves a file or folder inside an archive and returns the refreshed listing.
+/// Renaming a folder renames every entry below it.
+///
+/// # Arguments
+///
+/// * `app_handle` - The Tauri application handle (injected automatically).
+/// * `archive_path` - The path to the archive file to update.
+/// * `old_path` - The current path of the entry inside the archive.
+/// * `new_path` - The new path of the entry inside the archive. It must not exist yet.
+///
+/// # Returns
+///
+/// * `Ok(Vec<FileItem>)` - The updated list of items in the archive.
+/// * `Err(SoarZipError)` - The error if renaming fails, or `INVALID_INPUT` if the new path is already taken.
+///
+/// 重命名或移动压缩包内的文件或文件夹，并返回刷新后的列表。
+/// 重命名文件夹会重命名其下的所有条目。
+///
+/// # 参数
+///
+/// * `app_handle` - Tauri 应用程序句柄（自动注入）。
+/// * `archive_path` - 要更新的压缩文件路径。
+/// * `old_path` - 条目在压缩包内的当前路径。
+/// * `new_path` - 条目在压缩包内的新路径，必须尚不存在。
+///
+/// # 返回值
+///
+/// * `Ok(Vec<FileItem>)` - 压缩包中更新后的项目列表。
+/// * `Err(SoarZipError)` - 如果重命名失败则返回相应错误；如果新路径已被占用则返回 `INVALID_INPUT`。
+#[tauri::command]
+pub async fn rename_in_archive(
+    app_handle: AppHandle,
+    archive_path: String,
+    old_path: String,
+    new_path: String,
+) -> Result<Vec<FileItem>, SoarZipError> {
+    run_blocking(move || operations::rename_in_archive(&app_handle, archive_path, old_path, new_path)).await
+}
 
-    // Add specific files/folders to the arguments if provided
-    // 7-Zip generally handles '/' separators well, even on Windows
-    if !files_to_extract.is_empty() {
-        for file in files_to_extract {
-             args.push(file);
-        }
-    }
+/// Tests the integrity of an archive with the bundled 7-Zip (`7z t`).
+///
+/// Progress is emitted as `archive-progress` events with the operation "test".
+///
+/// # Arguments
+///
+/// * `app_handle` - The Tauri application handle (injected automatically).
+/// * `archive_path` - The path to the archive file to test.
+/// * `password` - Optional password for encrypted archives. It is never logged.
+/// * `job_id` - Optional id for this operation, usable with `cancel_operation` while it runs.
+///
+/// # Returns
+///
+/// * `Ok(ArchiveTestReport)` - Total, passed and failed entries with their errors, and an overall verdict.
+/// * `Err(SoarZipError)` - `PASSWORD_REQUIRED` if a password is needed, `OPERATION_CANCELLED` if the test was
+///   cancelled, or another error code if the test could not run.
+///
+/// 使用捆绑的 7-Zip（`7z t`）测试压缩包的完整性。
+///
+/// 进度以操作类型为 "test" 的 `archive-progress` 事件发出。
+///
+/// # 参数
+///
+/// * `app_handle` - Tauri 应用程序句柄（自动注入）。
+/// * `archive_path` - 要测试的压缩文件路径。
+/// * `password` - 用于加密压缩包的可选密码。该密码绝不会被记录到日志。
+/// * `job_id` - 此操作的可选 id，运行期间可用于 `cancel_operation`。
+///
+/// # 返回值
+///
+/// * `Ok(ArchiveTestReport)` - 条目总数、通过和失败的条目及其错误，以及总体结论。
+/// * `Err(SoarZipError)` - 如果需要密码则返回 `PASSWORD_REQUIRED`；如果测试被取消则返回 `OPERATION_CANCELLED`；
+///   如果测试无法运行，则返回其他错误码。
+#[tauri::command]
+pub async fn test_archive(
+    app_handle: AppHandle,
+    archive_path: String,
+    password: Option<String>,
+    job_id: Option<String>,
+) -> Result<ArchiveTestReport, SoarZipError> {
+    run_blocking(move || operations::test_archive(&app_handle, archive_path, password, job_id)).await
+}
 
-    // Execute the 7-Zip extraction command
-    let output = run_7z_command(&seven_zip_path, &args)?;
+/// Reads a single entry for the preview pane without extracting it (`7z e -so`).
+///
+/// Only the first `max_bytes` are decompressed. The result is decoded text with its detected
+/// encoding, a whole image with its MIME type, or a hex dump of the beginning of a binary entry.
+///
+/// # Arguments
+///
+/// * `app_handle` - The Tauri application handle (injected automatically).
+/// * `archive_path` - The path to the archive file.
+/// * `entry_path` - The path of the entry inside the archive.
+/// * `password` - Optional password for encrypted archives. It is never logged.
+/// * `max_bytes` - Optional cap on the bytes read; defaults to 4 MiB.
+///
+/// # Returns
+///
+/// * `Ok(EntryPreview)` - `{ kind: "text", text, encoding, truncated }`, `{ kind: "image", mime_type, data }`
+///   with base64 data, or `{ kind: "binary", hex_dump, mime_type, truncated }`.
+/// * `Err(SoarZipError)` - `PASSWORD_REQUIRED` if a password is needed, or another error code if reading fails.
+///
+/// 在不解压的情况下读取单个条目以供预览面板使用（`7z e -so`）。
+///
+/// 只解压前 `max_bytes` 个字节。结果为带有检测到的编码的已解码文本、带有 MIME 类型的完整图片，
+/// 或二进制条目开头部分的十六进制转储。
+///
+/// # 参数
+///
+/// * `app_handle` - Tauri 应用程序句柄（自动注入）。
+/// * `archive_path` - 压缩文件的路径。
+/// * `entry_path` - 条目在压缩包内的路径。
+/// * `password` - 用于加密压缩包的可选密码。该密码绝不会被记录到日志。
+/// * `max_bytes` - 读取字节数的可选上限；默认为 4 MiB。
+///
+/// # 返回值
+///
+/// * `Ok(EntryPreview)` - `{ kind: "text", text, encoding, truncated }`、数据为 base64 的
+///   `{ kind: "image", mime_type, data }`，或 `{ kind: "binary", hex_dump, mime_type, truncated }`。
+/// * `Err(SoarZipError)` - 如果需要密码则返回 `PASSWORD_REQUIRED`；如果读取失败，则返回其他错误码。
+#[tauri::command]
+pub async fn read_entry(
+    app_handle: AppHandle,
+    archive_path: String,
+    entry_path: String,
+    password: Option<String>,
+    max_bytes: Option<u64>,
+) -> Result<EntryPreview, SoarZipError> {
+    run_blocking(move || operations::read_entry(&app_handle, archive_path, entry_path, password, max_bytes)).await
+}
 
-    // Check the result of the 7-Zip command
-    if !output.status.success() {
-        let stderr_output = decode_7z_output(&output.stderr);
-        let error_msg = format!(
-            "Bundled 7-Zip extract command failed with exit code: {}. Error: {}",
-            output.status.code().unwrap_or(-1),
-            stderr_output.trim()
-        );
-        log_error(&error_msg);
-        return Err(error_msg);
-    }
+/// Searches the content of the entries of an archive for a substring or regular expression.
+///
+/// Entries are streamed through 7-Zip one at a time, except in solid archives, where the selection is
+/// extracted to a temporary folder in one pass; folders, binaries and entries above
+/// `options.max_file_size` are skipped. Every matching line is emitted as an `archive-search-match`
+/// event while the search runs, so results appear before it finishes.
+///
+/// # Arguments
+///
+/// * `app_handle` - The Tauri application handle (injected automatically).
+/// * `archive_path` - The path to the archive file, or a virtual path to a nested archive.
+/// * `pattern` - The text to find, or a regular expression if `options.regex` is set.
+/// * `password` - Optional password for encrypted archives. It is never logged.
+/// * `options` - Matching, size, context and scope options; omitted fields use their defaults.
+/// * `job_id` - Optional id for this search, usable with `cancel_operation` while it runs.
+///
+/// # Returns
+///
+/// * `Ok(SearchReport)` - How many entries were searched and matched, and which were skipped.
+/// * `Err(SoarZipError)` - `INVALID_INPUT` for an empty or invalid pattern, `OPERATION_CANCELLED` if the search
+///   was cancelled, `PASSWORD_REQUIRED` if a password is needed, or another error code if 7-Zip fails.
+///
+/// 在压缩包的条目内容中搜索子字符串或正则表达式。
+///
+/// 条目会逐个通过 7-Zip 流式读取，固实压缩包除外，其所选内容会被一次性解压到临时文件夹中；
+/// 文件夹、二进制文件以及超过 `options.max_file_size` 的条目会被跳过。
+/// 搜索运行期间，每个匹配行都会作为 `archive-search-match` 事件发出，因此结果会在搜索结束前显示。
+///
+/// # 参数
+///
+/// * `app_handle` - Tauri 应用程序句柄（自动注入）。
+/// * `archive_path` - 压缩文件的路径，或指向嵌套压缩包的虚拟路径。
+/// * `pattern` - 要查找的文本；如果设置了 `options.regex`，则为正则表达式。
+/// * `password` - 用于加密压缩包的可选密码。该密码绝不会被记录到日志。
+/// * `options` - 匹配、大小、上下文和范围选项；省略的字段使用默认值。
+/// * `job_id` - 此搜索的可选 id，运行期间可用于 `cancel_operation`。
+///
+/// # 返回值
+///
+/// * `Ok(SearchReport)` - 已搜索和已匹配的条目数，以及被跳过的条目。
+/// * `Err(SoarZipError)` - 模式为空或无效时返回 `INVALID_INPUT`；搜索被取消时返回 `OPERATION_CANCELLED`；
+///   需要密码时返回 `PASSWORD_REQUIRED`；7-Zip 失败时返回其他错误码。
+#[tauri::command]
+pub async fn search_archive_contents(
+    app_handle: AppHandle,
+    archive_path: String,
+    pattern: String,
+    password: Option<String>,
+    options: Option<SearchOptions>,
+    job_id: Option<String>,
+) -> Result<SearchReport, SoarZipError> {
+    let options = options.unwrap_or_default();
+    run_blocking(move || operations::search_archive_contents(&app_handle, archive_path, pattern, password, options, job_id)).await
+}
 
-    // Log success and potentially some output
-    let stdout_output = decode_7z_output(&output.stdout);
-    log_info("Bundled 7-Zip extract command executed successfully.");
-    if !stdout_output.is_empty() {
-         if stdout_output.len() < 500 { // Log short output fully
-             log_info(&format!("7-Zip output: {}", stdout_output.trim()));
-         } else { // Log length for long output
-             log_info(&format!("7-Zip output length: {}", stdout_output.len()));
-         }
-    } else {
-        log_info("7-Zip produced no output on stdout.");
-    }
+/// Opens an entry with its default application, like double-clicking it in a desktop archiver.
+///
+/// The entry is extracted to a per-session temporary directory and tracked there until the archive
+/// is closed (`close_archive`) or the application exits. Opening the same entry again reuses its file.
+///
+/// # Arguments
+///
+/// * `app_handle` - The Tauri application handle (injected automatically).
+/// * `archive_path` - The path to the archive file.
+/// * `entry_path` - The path of the entry inside the archive.
+/// * `password` - Optional password for encrypted archives. It is never logged.
+///
+/// # Returns
+///
+/// * `Ok(String)` - The path of the temporary file that was opened.
+/// * `Err(SoarZipError)` - `PASSWORD_REQUIRED` if a password is needed, or another error code if
+///   extracting or opening fails.
+///
+/// 用默认应用程序打开条目，就像在桌面压缩软件中双击它一样。
+///
+/// 条目会被解压到每个会话的临时目录中，并在那里被跟踪，直到压缩包被关闭（`close_archive`）或应用程序退出。
+/// 再次打开同一条目会复用其文件。
+///
+/// # 参数
+///
+/// * `app_handle` - Tauri 应用程序句柄（自动注入）。
+/// * `archive_path` - 压缩文件的路径。
+/// * `entry_path` - 条目在压缩包内的路径。
+/// * `password` - 用于加密压缩包的可选密码。该密码绝不会被记录到日志。
+///
+/// # 返回值
+///
+/// * `Ok(String)` - 被打开的临时文件的路径。
+/// * `Err(SoarZipError)` - 如果需要密码则返回 `PASSWORD_REQUIRED`；如果解压或打开失败，则返回其他错误码。
+#[tauri::command]
+pub async fn open_entry(
+    app_handle: AppHandle,
+    archive_path: String,
+    entry_path: String,
+    password: Option<String>,
+) -> Result<String, SoarZipError> {
+    run_blocking(move || operations::open_entry(&app_handle, archive_path, entry_path, password)).await
+}
 
-    Ok(())
-} 
\ No newline at end of file
+/// Writes an opened entry back into the archive after it was edited in another application.
+///
+/// The frontend calls this when the user confirms an `entry-modified` event. The archive is
+/// updated through a temporary copy that replaces it only on success.
+///
+/// # Arguments
+///
+/// * `app_handle` - The Tauri application handle (injected automatically).
+/// * `archive_path` - The path to the archive file.
+/// * `entry_path` - The path of the entry inside the archive, as passed to `open_entry`.
+/// * `password` - Optional password for encrypted archives. It is never logged.
+///
+/// # Returns
+///
+/// * `Ok(Vec<FileItem>)` - The updated listing of the archive.
+/// * `Err(SoarZipError)` - `INVALID_INPUT` if the entry was not opened in this session, or another
+///   error code if 7-Zip fails.
+///
+/// 在其他应用程序中编辑后，将已打开的条目写回压缩包。
+///
+/// 当用户确认 `entry-modified` 事件时由前端调用。压缩包通过临时副本更新，仅在成功后才替换原文件。
+///
+/// # 参数
+///
+/// * `app_handle` - Tauri 应用程序句柄（自动注入）。
+/// * `archive_path` - 压缩文件的路径。
+/// * `entry_path` - 条目在压缩包内的路径，与传给 `open_entry` 的相同。
+/// * `password` - 用于加密压缩包的可选密码。该密码绝不会被记录到日志。
+///
+/// # 返回值
+///
+/// * `Ok(Vec<FileItem>)` - 压缩包更新后的文件列表。
+/// * `Err(SoarZipError)` - 如果该条目未在本会话中打开则返回 `INVALID_INPUT`；如果 7-Zip 失败则返回其他错误码。
+#[tauri::command]
+pub async fn update_entry(
+    app_handle: AppHandle,
+    archive_path: String,
+    entry_path: String,
+    password: Option<String>,
+) -> Result<Vec<FileItem>, SoarZipError> {
+    run_blocking(move || operations::update_entry(&app_handle, archive_path, entry_path, password)).await
+}
+
+/// Deletes the temporary files of entries opened from an archive that is being closed.
+///
+/// # Arguments
+///
+/// * `temp_files` - The registry of temporary files (injected automatically).
+/// * `archive_path` - The path of the archive being closed.
+///
+/// # Returns
+///
+/// * `usize` - The number of temporary files that were removed.
+///
+/// 删除从正在关闭的压缩包中打开的条目的临时文件。
+///
+/// # 参数
+///
+/// * `temp_files` - 临时文件注册表（自动注入）。
+/// * `archive_path` - 正在关闭的压缩包的路径。
+///
+/// # 返回值
+///
+/// * `usize` - 被删除的临时文件数。
+#[tauri::command]
+pub fn close_archive(temp_files: State<TempFileRegistry>, archive_path: String) -> usize {
+    temp_files.remove_archive(&archive_path)
+}
+
+/// Cancels a running extraction or compression by killing its 7-Zip process.
+/// The cancelled command then returns `OPERATION_CANCELLED` after removing its partial output.
+///
+/// # Arguments
+///
+/// * `job_id` - The id of the operation to cancel.
+///
+/// # Returns
+///
+/// * `true` - If the operation was running and has been asked to stop.
+/// * `false` - If no running operation has this id (it may already have finished).
+///
+/// 通过终止 7-Zip 进程取消正在运行的解压或压缩操作。
+/// 被取消的命令会在删除其部分输出后返回 `OPERATION_CANCELLED`。
+///
+/// # 参数
+///
+/// * `job_id` - 要取消的操作 id。
+///
+/// # 返回值
+///
+/// * `true` - 如果该操作正在运行且已被要求停止。
+/// * `false` - 如果没有具有此 id 的运行中操作（它可能已经完成）。
+#[tauri::command]
+pub fn cancel_operation(jobs: State<'_, JobRegistry>, job_id: String) -> bool {
+    jobs.cancel(&job_id)
+}
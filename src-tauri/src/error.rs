@@ -0,0 +1,197 @@
+//! Structured errors returned by the backend to the frontend.
+//! 后端返回给前端的结构化错误。
+
+use std::fmt;
+use std::process::Output;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use super::archive_utils::decode_7z_output;
+
+/// An error of an archive operation, with a stable code the frontend can react to.
+///
+/// It is serialized as `{ "code": "...", "message": "..." }`. Codes never change once
+/// released; messages are meant for display and logs only.
+///
+/// 压缩包操作的错误，带有前端可据此作出反应的稳定错误码。
+///
+/// 序列化形式为 `{ "code": "...", "message": "..." }`。错误码一经发布便不再更改；
+/// 消息仅用于显示和日志。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SoarZipError {
+    /// The archive (or another input file) does not exist / 压缩包（或其他输入文件）不存在
+    ArchiveNotFound(String),
+    /// The archive is encrypted and the password is missing or wrong / 压缩包已加密且密码缺失或错误
+    PasswordRequired,
+    /// There is not enough free space on the target disk / 目标磁盘空间不足
+    DiskFull(String),
+    /// 7-Zip cannot read the file as an archive, or the format does not support the request / 7-Zip 无法将文件作为压缩包读取，或格式不支持该请求
+    UnsupportedFormat(String),
+    /// The bundled 7-Zip executable is missing or cannot be started / 捆绑的 7-Zip 可执行文件缺失或无法启动
+    SevenZipMissing(String),
+    /// The operation was cancelled through `cancel_operation` / 操作已通过 `cancel_operation` 取消
+    Cancelled,
+    /// 7-Zip exit code 1: finished with warnings (e.g., locked files) / 7-Zip 退出码 1：完成但有警告（例如文件被锁定）
+    Warning(String),
+    /// 7-Zip exit code 2: fatal error / 7-Zip 退出码 2：致命错误
+    Fatal(String),
+    /// 7-Zip exit code 7: command line error / 7-Zip 退出码 7：命令行错误
+    CommandLine(String),
+    /// 7-Zip exit code 8: not enough memory / 7-Zip 退出码 8：内存不足
+    OutOfMemory(String),
+    /// 7-Zip exit code 255: the 7-Zip process was stopped / 7-Zip 退出码 255：7-Zip 进程被停止
+    UserStopped(String),
+    /// The request itself is invalid (bad path, empty selection, name collision, ...) / 请求本身无效（路径错误、选择为空、名称冲突等）
+    InvalidInput(String),
+    /// A file system operation outside 7-Zip failed / 7-Zip 之外的文件系统操作失败
+    Io(String),
+    /// Entries would be written outside the output directory and the policy refuses them / 条目会被写到输出目录之外，且策略拒绝了它们
+    UnsafePaths(String),
+    /// The archive exceeds a configured extraction limit (size, ratio, entry count, path depth or archive nesting) / 压缩包超出了配置的解压限制（大小、压缩比、条目数、路径深度或压缩包嵌套层数）
+    LimitExceeded(String),
+}
+
+impl SoarZipError {
+    /// The stable code of this error.
+    ///
+    /// 此错误的稳定错误码。
+    pub fn code(&self) -> &'static str {
+        match self {
+            SoarZipError::ArchiveNotFound(_) => "ARCHIVE_NOT_FOUND",
+            SoarZipError::PasswordRequired => "PASSWORD_REQUIRED",
+            SoarZipError::DiskFull(_) => "DISK_FULL",
+            SoarZipError::UnsupportedFormat(_) => "UNSUPPORTED_FORMAT",
+            SoarZipError::SevenZipMissing(_) => "SEVEN_ZIP_MISSING",
+            SoarZipError::Cancelled => "OPERATION_CANCELLED",
+            SoarZipError::Warning(_) => "SEVEN_ZIP_WARNING",
+            SoarZipError::Fatal(_) => "SEVEN_ZIP_FATAL",
+            SoarZipError::CommandLine(_) => "SEVEN_ZIP_COMMAND_LINE",
+            SoarZipError::OutOfMemory(_) => "OUT_OF_MEMORY",
+            SoarZipError::UserStopped(_) => "USER_STOPPED",
+            SoarZipError::InvalidInput(_) => "INVALID_INPUT",
+            SoarZipError::Io(_) => "IO_ERROR",
+            SoarZipError::UnsafePaths(_) => "UNSAFE_ENTRY_PATHS",
+            SoarZipError::LimitExceeded(_) => "EXTRACTION_LIMIT_EXCEEDED",
+        }
+    }
+
+    /// The process exit code used for this error in command-line mode.
+    /// It follows 7-Zip's own convention so scripts can treat both alike.
+    ///
+    /// 命令行模式下此错误对应的进程退出码。
+    /// 它遵循 7-Zip 自身的约定，使脚本可以同样对待两者。
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            SoarZipError::Warning(_) => 1,
+            SoarZipError::CommandLine(_) | SoarZipError::InvalidInput(_) => 7,
+            SoarZipError::OutOfMemory(_) => 8,
+            SoarZipError::Cancelled | SoarZipError::UserStopped(_) => 255,
+            _ => 2,
+        }
+    }
+
+    /// Classifies a failed 7-Zip run from its exit code and output.
+    ///
+    /// Known stderr patterns (wrong password, disk full, unknown format) take precedence
+    /// over the exit code, since 7-Zip reports all of them as fatal errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - The output of the finished 7-Zip process.
+    /// * `action` - A short name of the operation (e.g., "add"), used in the message.
+    ///
+    /// 根据退出码和输出对失败的 7-Zip 运行进行分类。
+    ///
+    /// 已知的 stderr 模式（密码错误、磁盘已满、未知格式）优先于退出码，
+    /// 因为 7-Zip 会将它们全部报告为致命错误。
+    ///
+    /// # 参数
+    ///
+    /// * `output` - 已结束的 7-Zip 进程的输出。
+    /// * `action` - 操作的简短名称（例如 "add"），用于消息中。
+    pub fn from_7z_output(output: &Output, action: &str) -> Self {
+        let stderr_output = decode_7z_output(&output.stderr);
+        let stdout_output = decode_7z_output(&output.stdout);
+        let exit_code = output.status.code().unwrap_or(-1);
+        let message = format!(
+            "Bundled 7-Zip {} command failed with exit code: {}. Error: {}",
+            action,
+            exit_code,
+            stderr_output.trim()
+        );
+
+        let combined = format!("{}\n{}", stderr_output, stdout_output).to_lowercase();
+        if is_password_error(&combined) {
+            return SoarZipError::PasswordRequired;
+        }
+        if combined.contains("not enough space on the disk")
+            || combined.contains("no space left on device")
+            || combined.contains("disk full")
+        {
+            return SoarZipError::DiskFull(message);
+        }
+        if combined.contains("can not open the file as archive")
+            || combined.contains("cannot open the file as archive")
+            || combined.contains("is not supported archive")
+            || combined.contains("unsupported archive")
+        {
+            return SoarZipError::UnsupportedFormat(message);
+        }
+        if combined.contains("cannot find archive") {
+            return SoarZipError::ArchiveNotFound(message);
+        }
+
+        match exit_code {
+            1 => SoarZipError::Warning(message),
+            7 => SoarZipError::CommandLine(message),
+            8 => SoarZipError::OutOfMemory(message),
+            255 => SoarZipError::UserStopped(message),
+            _ => SoarZipError::Fatal(message),
+        }
+    }
+}
+
+/// Recognizes 7-Zip's messages for encrypted content that could not be opened with the given password.
+///
+/// 识别 7-Zip 关于无法使用给定密码打开加密内容的消息。
+pub(crate) fn is_password_error(output: &str) -> bool {
+    let lower = output.to_lowercase();
+    lower.contains("wrong password")
+        || lower.contains("can not open encrypted archive")
+        || lower.contains("cannot open encrypted archive")
+        || lower.contains("enter password")
+}
+
+impl fmt::Display for SoarZipError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SoarZipError::PasswordRequired => {
+                write!(f, "The archive is encrypted and the password is missing or wrong.")
+            }
+            SoarZipError::Cancelled => write!(f, "The operation was cancelled."),
+            SoarZipError::ArchiveNotFound(message)
+            | SoarZipError::DiskFull(message)
+            | SoarZipError::UnsupportedFormat(message)
+            | SoarZipError::SevenZipMissing(message)
+            | SoarZipError::Warning(message)
+            | SoarZipError::Fatal(message)
+            | SoarZipError::CommandLine(message)
+            | SoarZipError::OutOfMemory(message)
+            | SoarZipError::UserStopped(message)
+            | SoarZipError::InvalidInput(message)
+            | SoarZipError::Io(message)
+            | SoarZipError::UnsafePaths(message)
+            | SoarZipError::LimitExceeded(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for SoarZipError {}
+
+impl Serialize for SoarZipError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("SoarZipError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
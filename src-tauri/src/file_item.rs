@@ -2,7 +2,7 @@ use serde::{Serialize, Deserialize};
 
 /// Represents an item (file or directory) within an archive.
 /// 表示压缩包内的一个项目（文件或目录）。
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct FileItem {
     /// The full path of the item within the archive, using '/' as separator.
     pub name: String,
@@ -14,4 +14,31 @@ pub struct FileItem {
     pub modified_date: String,
     /// A descriptive name for the type of the item (e.g., "Text Document", "Folder").
     pub type_name: String,
+    /// The compressed size of the item in bytes, if the format reports it per entry.
+    #[serde(default)]
+    pub packed_size: Option<u64>,
+    /// The CRC32 checksum of the item as printed by 7-Zip (hexadecimal).
+    #[serde(default)]
+    pub crc: Option<String>,
+    /// The compression (and encryption) method, e.g. "LZMA2:24" or "AES-256 Deflate".
+    #[serde(default)]
+    pub method: Option<String>,
+    /// Whether the item is encrypted.
+    #[serde(default)]
+    pub encrypted: bool,
+    /// File attributes or Unix permissions as printed by 7-Zip (e.g., "A", "-rw-r--r--").
+    #[serde(default)]
+    pub attributes: Option<String>,
+    /// The creation date of the item, if stored in the archive.
+    #[serde(default)]
+    pub created_date: Option<String>,
+    /// The last access date of the item, if stored in the archive.
+    #[serde(default)]
+    pub accessed_date: Option<String>,
+    /// The comment attached to the item, if any.
+    #[serde(default)]
+    pub comment: Option<String>,
+    /// The target of a symbolic link item.
+    #[serde(default)]
+    pub symlink_target: Option<String>,
 } 
\ No newline at end of file
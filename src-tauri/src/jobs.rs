@@ -0,0 +1,218 @@
+//! Registry of running archive operations, used to cancel them.
+//! 正在运行的压缩包操作的注册表，用于取消操作。
+
+use std::collections::HashMap;
+use std::process::Child;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use super::logging::{log_info, log_error};
+use super::error::SoarZipError;
+
+/// Shared handle to one running operation and the 7-Zip process it is driving.
+/// 指向一个正在运行的操作及其驱动的 7-Zip 进程的共享句柄。
+#[derive(Clone)]
+pub struct JobHandle {
+    id: String,
+    cancelled: Arc<AtomicBool>,
+    child: Arc<Mutex<Option<Child>>>,
+    abort_reason: Arc<Mutex<Option<SoarZipError>>>,
+}
+
+impl JobHandle {
+    fn new(id: String) -> Self {
+        JobHandle {
+            id,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            child: Arc::new(Mutex::new(None)),
+            abort_reason: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// The identifier the frontend uses to refer to this operation.
+    ///
+    /// 前端用于引用此操作的标识符。
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Whether cancellation has been requested.
+    ///
+    /// 是否已请求取消。
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Stores the spawned 7-Zip process so it can be killed on cancellation.
+    /// If cancellation was requested before the process started, it is killed right away.
+    ///
+    /// 保存已启动的 7-Zip 进程，以便在取消时将其终止。
+    /// 如果在进程启动前已请求取消，则立即终止该进程。
+    pub fn attach_child(&self, child: Child) {
+        // The flag is checked under the lock that `cancel` also takes, so either this check sees the
+        // cancellation or `cancel` finds the stored process
+        // 在 `cancel` 也会获取的锁下检查标志，因此要么此处看到取消请求，要么 `cancel` 能找到已保存的进程
+        let mut guard = self.child.lock().unwrap();
+        let child = guard.insert(child);
+        if self.is_cancelled() {
+            let _ = child.kill();
+        }
+    }
+
+    /// Polls the attached process for its exit status without blocking cancellation.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(status))` - The process has exited (it is detached from the handle).
+    /// * `Ok(None)` - The process is still running.
+    /// * `Err(SoarZipError)` - No process is attached or polling failed.
+    ///
+    /// 轮询已关联进程的退出状态，且不会阻塞取消操作。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Some(status))` - 进程已退出（并已从句柄分离）。
+    /// * `Ok(None)` - 进程仍在运行。
+    /// * `Err(SoarZipError)` - 没有关联的进程或轮询失败。
+    pub fn try_wait_child(&self) -> Result<Option<std::process::ExitStatus>, SoarZipError> {
+        let mut guard = self.child.lock().unwrap();
+        let child = guard
+            .as_mut()
+            .ok_or_else(|| SoarZipError::Io("No 7-Zip process is attached to this job".to_string()))?;
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                guard.take();
+                Ok(Some(status))
+            }
+            Ok(None) => Ok(None),
+            Err(e) => Err(SoarZipError::Io(format!("Failed to wait for 7-Zip process: {}", e))),
+        }
+    }
+
+    /// Stops the operation from inside the backend (e.g., when a safety limit is hit).
+    /// The 7-Zip process is killed as on cancellation, but the operation fails with `error`.
+    ///
+    /// 从后端内部停止操作（例如触及安全限制时）。
+    /// 7-Zip 进程会像取消时一样被终止，但操作以 `error` 失败。
+    pub fn abort(&self, error: SoarZipError) {
+        log_error(&format!("Aborting job {}: {}", self.id, error));
+        *self.abort_reason.lock().unwrap() = Some(error);
+        self.cancel();
+    }
+
+    /// The error a stopped operation fails with: the abort reason if it was aborted, otherwise `Cancelled`.
+    ///
+    /// 已停止的操作所返回的错误：如果是被中止则为中止原因，否则为 `Cancelled`。
+    pub fn stop_error(&self) -> SoarZipError {
+        self.abort_reason.lock().unwrap().clone().unwrap_or(SoarZipError::Cancelled)
+    }
+
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        if let Some(child) = self.child.lock().unwrap().as_mut() {
+            if let Err(e) = child.kill() {
+                log_error(&format!("Failed to kill 7-Zip process for job {}: {}", self.id, e));
+            }
+        }
+    }
+}
+
+/// Managed Tauri state holding every cancellable operation that is currently running.
+/// 保存当前所有可取消的运行中操作的 Tauri 托管状态。
+#[derive(Default)]
+pub struct JobRegistry {
+    jobs: Arc<Mutex<HashMap<String, JobHandle>>>,
+    counter: AtomicU64,
+}
+
+impl JobRegistry {
+    /// Registers a new operation. The frontend may supply its own id so it can cancel the
+    /// operation before the command returns; otherwise one is generated.
+    ///
+    /// The returned guard removes the job from the registry when dropped.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ActiveJob)` - The guard of the registered job.
+    /// * `Err(SoarZipError)` - `InvalidInput` if a running job already has the supplied id.
+    ///
+    /// 注册一个新操作。前端可以提供自己的 id，以便在命令返回前取消该操作；否则会自动生成一个。
+    ///
+    /// 返回的守卫在被丢弃时会将该作业从注册表中移除。
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(ActiveJob)` - 已注册作业的守卫。
+    /// * `Err(SoarZipError)` - 如果已有运行中的作业使用了所提供的 id，则返回 `InvalidInput`。
+    pub fn register(&self, job_id: Option<String>) -> Result<ActiveJob, SoarZipError> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let id = match job_id.filter(|id| !id.is_empty()) {
+            Some(id) if jobs.contains_key(&id) => {
+                let error_msg = format!("A running operation already uses the id '{}'", id);
+                log_error(&error_msg);
+                return Err(SoarZipError::InvalidInput(error_msg));
+            }
+            Some(id) => id,
+            // Generated ids skip any id the frontend happens to use / 生成的 id 会跳过前端恰好使用的任何 id
+            None => loop {
+                let id = format!("job-{}", self.counter.fetch_add(1, Ordering::SeqCst) + 1);
+                if !jobs.contains_key(&id) {
+                    break id;
+                }
+            },
+        };
+        let handle = JobHandle::new(id.clone());
+        jobs.insert(id.clone(), handle.clone());
+        log_info(&format!("Registered job: {}", id));
+        Ok(ActiveJob { handle, jobs: Arc::clone(&self.jobs) })
+    }
+
+    /// Requests cancellation of a running operation and kills its 7-Zip process.
+    ///
+    /// # Returns
+    ///
+    /// * `true` - If the job was found and cancellation was requested.
+    /// * `false` - If no running job has this id (it may already have finished).
+    ///
+    /// 请求取消正在运行的操作并终止其 7-Zip 进程。
+    ///
+    /// # 返回值
+    ///
+    /// * `true` - 如果找到了该作业并已请求取消。
+    /// * `false` - 如果没有具有此 id 的运行中作业（它可能已经完成）。
+    pub fn cancel(&self, job_id: &str) -> bool {
+        let handle = self.jobs.lock().unwrap().get(job_id).cloned();
+        match handle {
+            Some(handle) => {
+                log_info(&format!("Cancelling job: {}", job_id));
+                handle.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A registered job that unregisters itself when dropped.
+/// 已注册的作业，在被丢弃时自动注销。
+pub struct ActiveJob {
+    handle: JobHandle,
+    jobs: Arc<Mutex<HashMap<String, JobHandle>>>,
+}
+
+impl ActiveJob {
+    /// The shared handle of this job.
+    ///
+    /// 此作业的共享句柄。
+    pub fn handle(&self) -> &JobHandle {
+        &self.handle
+    }
+}
+
+impl Drop for ActiveJob {
+    fn drop(&mut self) {
+        if let Ok(mut jobs) = self.jobs.lock() {
+            jobs.remove(&self.handle.id);
+        }
+    }
+}
@@ -1,15 +1,33 @@
 // Declare the modules we created
 pub mod file_item;
 pub mod logging;
+pub mod error;
 pub mod archive_utils;
+pub mod archive_info;
+pub mod archive_create;
+pub mod archive_extract;
+pub mod archive_edit;
+pub mod archive_test;
+pub mod archive_preview;
+pub mod archive_search;
+pub mod temp_files;
+pub mod nested_archive;
+pub mod progress;
+pub mod jobs;
+pub mod operations;
 pub mod commands;
+pub mod cli;
 
+use std::io::Write;
 use std::sync::Mutex;
 use tauri_plugin_cli::CliExt;
 use tauri::Manager;
 
 // Re-export the commands to make them accessible for the handler
 use commands::*;
+use jobs::JobRegistry;
+use temp_files::TempFileRegistry;
+use archive_edit::ArchiveLocks;
 
 // State to hold the initial file path passed via CLI arguments
 struct CliFilePathState {
@@ -36,6 +54,19 @@ fn get_initial_file_path(state: tauri::State<CliFilePathState>) -> Option<String
     state.take_path()
 }
 
+/// Attaches to the console of the parent process, so that output of CLI subcommands is visible
+/// even though release builds use the Windows GUI subsystem. A no-op elsewhere.
+///
+/// 附加到父进程的控制台，使 CLI 子命令的输出在发布版本使用 Windows GUI 子系统时仍然可见。
+/// 在其他平台上不执行任何操作。
+fn attach_parent_console() {
+    #[cfg(windows)]
+    unsafe {
+        use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
 /// The main entry point for the Tauri application logic.
 /// Configures and runs the Tauri application.
 ///
@@ -46,6 +77,12 @@ pub fn run() {
     tauri::Builder::default()
         // Manage the state for the initial file path
         .manage(CliFilePathState { path: Mutex::new(None) })
+        // Manage the registry of running, cancellable archive operations
+        .manage(JobRegistry::default())
+        // Manage the locks that keep edits of the same archive from overlapping
+        .manage(ArchiveLocks::default())
+        // Manage the temporary files of entries opened with other applications
+        .manage(TempFileRegistry::default())
         // Initialize external plugins
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_cli::init())
@@ -54,6 +91,17 @@ pub fn run() {
             let state = app.state::<CliFilePathState>();
             match app.cli().matches() {
                 Ok(matches) => {
+                    // A subcommand runs headlessly: the window stays hidden and the process exits with its code
+                    // 子命令以无界面方式运行：窗口保持隐藏，进程以其退出码退出
+                    if let Some(subcommand) = &matches.subcommand {
+                        attach_parent_console();
+                        let exit_code = cli::run_subcommand(app.handle(), subcommand);
+                        let _ = std::io::stdout().flush();
+                        // process::exit skips the exit handler, so nested archives extracted by the subcommand are removed here
+                        // process::exit 会跳过退出处理程序，因此子命令解压的嵌套压缩包在此删除
+                        app.state::<TempFileRegistry>().remove_all();
+                        std::process::exit(exit_code);
+                    }
                     if let Some(arg_data) = matches.args.get("filePath") {
                         // arg_data.value is assumed to be serde_json::Value directly
                         if let Some(path_str) = arg_data.value.as_str() {
@@ -69,6 +117,14 @@ pub fn run() {
                     eprintln!("Failed to parse CLI arguments: {}", e);
                 }
             }
+            // Watch entries opened with other applications for edits
+            // 监视用其他应用程序打开的条目是否被编辑
+            app.state::<TempFileRegistry>().start_watcher(app.handle().clone());
+            // The main window starts hidden so that headless runs never flash it
+            // 主窗口以隐藏状态启动，使无界面运行时不会闪现窗口
+            if let Some(window) = app.get_webview_window("main") {
+                window.show()?;
+            }
             Ok(())
         })
         // Register the invoke handler with all exported commands
@@ -84,10 +140,27 @@ pub fn run() {
             // Archive commands
             open_archive,
             extract_files,
+            create_archive,
+            add_to_archive,
+            delete_files_in_archive,
+            rename_in_archive,
+            test_archive,
+            read_entry,
+            search_archive_contents,
+            open_entry,
+            update_entry,
+            close_archive,
+            cancel_operation,
             // New command
             get_initial_file_path
         ])
-        // Run the application
-        .run(tauri::generate_context!())
-        .expect("Error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("Error while building tauri application")
+        // Run the application; opened entries are deleted when it exits
+        // 运行应用程序；退出时删除已打开的条目
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                app_handle.state::<TempFileRegistry>().remove_all();
+            }
+        });
 }
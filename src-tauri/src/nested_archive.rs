@@ -0,0 +1,152 @@
+//! Archives stored inside other archives, addressed by virtual paths such as
+//! `outer.7z!/inner/data.zip`. Each inner archive is extracted to the session's temporary
+//! directory once and then handled like any archive on disk.
+//!
+//! 存储在其他压缩包内的压缩包，通过 `outer.7z!/inner/data.zip` 这样的虚拟路径访问。
+//! 每个内层压缩包只会被解压到会话临时目录一次，之后像磁盘上的任何压缩包一样处理。
+
+use std::path::{Path, PathBuf};
+
+use super::logging::{log_info, log_error};
+use super::archive_utils::list_archive_entries;
+use super::archive_extract::{ExtractionLimits, check_disk_space, check_extraction_limits, extract_entry_to_file};
+use super::temp_files::{TempFile, TempFileRegistry};
+use super::error::SoarZipError;
+
+/// Separates an archive from the path of an archive entry inside it / 分隔压缩包与其内部压缩包条目路径的分隔符
+pub const NESTED_ARCHIVE_SEPARATOR: &str = "!/";
+
+/// Splits a virtual path into the archive that contains the innermost archive and that
+/// archive's entry path, e.g. `a.7z!/b.zip!/c/d.tar` into `a.7z!/b.zip` and `c/d.tar`.
+/// Returns `None` for a plain path.
+///
+/// 将虚拟路径拆分为包含最内层压缩包的压缩包和该压缩包的条目路径，
+/// 例如将 `a.7z!/b.zip!/c/d.tar` 拆分为 `a.7z!/b.zip` 和 `c/d.tar`。对于普通路径返回 `None`。
+pub fn split_nested_path(archive_path: &str) -> Option<(&str, &str)> {
+    archive_path
+        .rsplit_once(NESTED_ARCHIVE_SEPARATOR)
+        .filter(|(parent, entry)| !parent.is_empty() && !entry.is_empty())
+}
+
+/// Whether a path addresses an archive inside another archive. A file that really exists
+/// under that name is taken as a plain path.
+///
+/// 路径是否指向另一个压缩包内的压缩包。如果确实存在同名文件，则视为普通路径。
+pub fn is_nested_path(archive_path: &str) -> bool {
+    split_nested_path(archive_path).is_some() && !Path::new(archive_path).is_file()
+}
+
+/// Whether `archive_path` is `root` itself or an archive nested anywhere inside it.
+/// 判断 `archive_path` 是否为 `root` 本身或嵌套在其中任意层级的压缩包。
+pub fn is_within(archive_path: &str, root: &str) -> bool {
+    archive_path
+        .strip_prefix(root)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(NESTED_ARCHIVE_SEPARATOR))
+}
+
+/// Returns the file on disk for an archive path, extracting nested archives level by level.
+///
+/// Extracted archives are tracked by the [`TempFileRegistry`] under their containing archive,
+/// so they are reused while browsing and deleted together with it. The same password is used
+/// for every level. The number of levels and each inner archive are checked against the limits
+/// before anything is extracted, since an archive can contain copies of itself.
+///
+/// # Arguments
+///
+/// * `seven_zip_path` - The path to the 7-Zip executable.
+/// * `registry` - The session's temporary files.
+/// * `archive_path` - A plain or virtual archive path.
+/// * `password` - Optional password for encrypted archives.
+/// * `limits` - The limits for the nesting depth and for each inner archive.
+///
+/// # Returns
+///
+/// * `Ok(PathBuf)` - The archive file to hand to 7-Zip.
+/// * `Err(SoarZipError)` - `ArchiveNotFound` if the outermost archive or an inner entry does not exist,
+///   `LimitExceeded` if a limit is exceeded, `DiskFull` if an inner archive does not fit, or the error
+///   if extracting an inner archive fails.
+///
+/// 返回压缩包路径对应的磁盘文件，逐层解压嵌套的压缩包。
+///
+/// 解压出的压缩包由 [`TempFileRegistry`] 按其所在的压缩包进行跟踪，因此浏览时会被复用，
+/// 并与所在的压缩包一起被删除。每一层都使用同一个密码。由于压缩包可以包含其自身的副本，
+/// 在解压任何内容之前，都会根据限制检查嵌套层数和每个内层压缩包。
+///
+/// # 参数
+///
+/// * `seven_zip_path` - 7-Zip 可执行文件的路径。
+/// * `registry` - 本会话的临时文件。
+/// * `archive_path` - 普通或虚拟的压缩包路径。
+/// * `password` - 用于加密压缩包的可选密码。
+/// * `limits` - 嵌套深度以及每个内层压缩包的限制。
+///
+/// # 返回值
+///
+/// * `Ok(PathBuf)` - 要交给 7-Zip 的压缩包文件。
+/// * `Err(SoarZipError)` - 如果最外层压缩包或内层条目不存在则返回 `ArchiveNotFound`；超出限制时返回 `LimitExceeded`；
+///   内层压缩包放不下时返回 `DiskFull`；如果解压内层压缩包失败则返回相应错误。
+pub fn resolve_archive_path(
+    seven_zip_path: &Path,
+    registry: &TempFileRegistry,
+    archive_path: &str,
+    password: Option<&str>,
+    limits: &ExtractionLimits,
+) -> Result<PathBuf, SoarZipError> {
+    if Path::new(archive_path).exists() {
+        return Ok(PathBuf::from(archive_path));
+    }
+    let Some((parent_path, entry_path)) = split_nested_path(archive_path) else {
+        let error_msg = format!("Archive file not found: {}", archive_path);
+        log_error(&error_msg);
+        return Err(SoarZipError::ArchiveNotFound(error_msg));
+    };
+
+    if let Some(file) = registry.find(parent_path, entry_path) {
+        return Ok(file.path);
+    }
+    if let Some(max_nesting_depth) = limits.max_nesting_depth {
+        let depth = archive_path.matches(NESTED_ARCHIVE_SEPARATOR).count();
+        if depth > max_nesting_depth {
+            return Err(SoarZipError::LimitExceeded(format!(
+                "'{}' is nested {} archives deep, more than the limit of {}.",
+                archive_path, depth, max_nesting_depth
+            )));
+        }
+    }
+    let parent_file = resolve_archive_path(seven_zip_path, registry, parent_path, password, limits)?;
+    let parent_file_str = parent_file.to_string_lossy();
+
+    // The inner archive is checked like any extracted entry before it is written
+    // 内层压缩包在写入之前会像任何被解压的条目一样接受检查
+    let entries = list_archive_entries(seven_zip_path, &parent_file_str, password)?;
+    let Some(item) = entries.iter().find(|item| !item.is_dir && item.name == entry_path) else {
+        let error_msg = format!("Archive '{}' not found in {}", entry_path, parent_path);
+        log_error(&error_msg);
+        return Err(SoarZipError::ArchiveNotFound(error_msg));
+    };
+    check_extraction_limits(&[item], limits)?;
+    let temp_path = registry.allocate(entry_path)?;
+    check_disk_space(&temp_path, item.size, 0)?;
+    extract_entry_to_file(seven_zip_path, &parent_file_str, entry_path, password, &temp_path)?;
+    registry.register(TempFile {
+        archive_path: parent_path.to_string(),
+        entry_path: entry_path.to_string(),
+        path: temp_path.clone(),
+    });
+    log_info(&format!("Extracted nested archive '{}' to {}", archive_path, temp_path.display()));
+    Ok(temp_path)
+}
+
+/// Refuses to modify an archive inside another archive: the change would only reach the
+/// temporary copy and be lost when the outer archive is closed.
+///
+/// 拒绝修改另一个压缩包内的压缩包：更改只会作用于临时副本，并在外层压缩包关闭时丢失。
+pub fn ensure_not_nested(archive_path: &str) -> Result<(), SoarZipError> {
+    if is_nested_path(archive_path) {
+        return Err(SoarZipError::InvalidInput(format!(
+            "'{}' is inside another archive and cannot be modified. Extract it first to edit it.",
+            archive_path
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,650 @@
+//! Blocking implementations of the archive operations.
+//! The Tauri commands run these on a background worker so slow 7-Zip calls never block the IPC handler.
+//!
+//! 压缩包操作的阻塞实现。
+//! Tauri 命令会在后台工作线程中运行这些函数，使缓慢的 7-Zip 调用不会阻塞 IPC 处理程序。
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_opener::OpenerExt;
+
+use super::file_item::FileItem;
+use super::archive_info::OpenedArchive;
+use super::logging::{log_info, log_error};
+use super::archive_utils::{
+    resolve_7z_path, check_7z_output, list_archive, list_archive_entries, password_switch, run_with_list_file, select_entries,
+};
+use super::archive_create::{ArchiveFormat, CreatedArchive, EncryptionOptions, create_archive_with_7z, total_source_size};
+use super::archive_extract::{
+    ExtractLayout, ExtractOptions, ExtractionLimits, ExtractionReport, OutputSizeWatchdog, OverwritePolicy, PathMode, StagingDirectory,
+    UnsafePathPolicy, check_disk_space, check_extraction_limits, extract_entry_to_file, find_conflicts,
+    find_unsafe_entries, merge_directory, plan_entries, resolve_destination, smart_output_directory,
+};
+use super::archive_edit::{
+    AddConflictPolicy, ArchiveLocks, add_to_archive_with_7z, delete_from_archive_with_7z, rename_in_archive_with_7z, update_entry_with_7z,
+};
+use super::archive_test::{ArchiveTestReport, test_archive_with_7z};
+use super::archive_preview::{DEFAULT_PREVIEW_MAX_BYTES, EntryPreview, build_preview, read_entry_bytes};
+use super::archive_search::{SEARCH_MATCH_EVENT, SearchOptions, SearchReport, build_matcher, search_entries};
+use super::progress::{ProgressReporter, run_7z_with_progress};
+use super::jobs::JobRegistry;
+use super::temp_files::{TempFile, TempFileRegistry};
+use super::nested_archive::{ensure_not_nested, resolve_archive_path};
+use super::error::SoarZipError;
+
+/// Lists an archive. Blocking implementation of [`crate::commands::open_archive`].
+/// 列出压缩包内容。[`crate::commands::open_archive`] 的阻塞实现。
+pub fn open_archive(app_handle: &AppHandle, archive_path: String, password: Option<String>) -> Result<OpenedArchive, SoarZipError> {
+    log_info(&format!("Attempting to open archive: {}", archive_path));
+
+    // Resolve the path to the bundled 7-Zip executable
+    let seven_zip_path = resolve_7z_path(app_handle)?;
+    log_info(&format!("Using bundled 7-Zip at: {:?}", seven_zip_path));
+
+    // An archive inside another archive is extracted to a temporary file first
+    // 另一个压缩包内的压缩包会先被解压到临时文件
+    let archive_file = resolve_archive_path(&seven_zip_path, &app_handle.state::<TempFileRegistry>(), &archive_path, password.as_deref(), &ExtractionLimits::default())?;
+
+    // List and parse the archive contents and properties (`7z l -slt`)
+    let opened = list_archive(&seven_zip_path, &archive_file.to_string_lossy(), password.as_deref())?;
+
+    log_info(&format!("Successfully listed archive: {} (type: {})", archive_path, opened.info.archive_type));
+    Ok(opened)
+}
+
+/// Extracts entries from an archive. Blocking implementation of [`crate::commands::extract_files`].
+/// 从压缩包中解压条目。[`crate::commands::extract_files`] 的阻塞实现。
+pub fn extract_files(
+    app_handle: &AppHandle,
+    archive_path: String,
+    files_to_extract: Vec<String>, // List of relative paths inside the archive
+    output_directory: String,
+    password: Option<String>,
+    options: ExtractOptions,
+    job_id: Option<String>,
+) -> Result<ExtractionReport, SoarZipError> {
+    let mut files_to_extract = files_to_extract;
+    log_info(&format!(
+        "Starting extraction to: {}, Archive: {}, Options: {:?}",
+        output_directory, archive_path, options
+    ));
+    if !files_to_extract.is_empty() {
+        log_info(&format!("Files/folders to extract: {:?}", files_to_extract));
+    } else {
+        log_info("Extracting all contents.");
+    }
+    // Everything, relative to a folder, means everything inside that folder
+    // 相对于某个文件夹的"全部内容"是指该文件夹内的全部内容
+    if options.path_mode == PathMode::Relative && files_to_extract.is_empty() && !options.base_path.trim_matches('/').is_empty() {
+        files_to_extract.push(options.base_path.clone());
+    }
+
+    // Resolve 7-Zip path
+    let seven_zip_path = resolve_7z_path(app_handle)?;
+    log_info(&format!("Using bundled 7-Zip for extraction: {:?}", seven_zip_path));
+    let archive_path = resolve_archive_path(&seven_zip_path, &app_handle.state::<TempFileRegistry>(), &archive_path, password.as_deref(), &options.limits)?
+        .to_string_lossy()
+        .into_owned();
+
+    // The listing provides the uncompressed size of the selection, which 7-Zip's percentage refers to,
+    // and the stored paths, which are checked before anything is written
+    // 列表提供所选内容的未压缩大小（7-Zip 的百分比即相对于该大小），以及在写入任何内容之前要检查的存储路径
+    let entries = list_archive_entries(&seven_zip_path, &archive_path, password.as_deref())?;
+    let selected = select_entries(&entries, &files_to_extract);
+    // The sizes come from the archive headers and may be forged to overflow / 大小来自压缩包头部，可能被伪造以造成溢出
+    let total_bytes = selected.iter().fold(0u64, |sum, item| sum.saturating_add(item.size));
+
+    let blocked_entries = find_unsafe_entries(&selected);
+    if !blocked_entries.is_empty() {
+        log_error(&format!(
+            "Found {} entries with unsafe paths (policy: {:?}): {:?}",
+            blocked_entries.len(),
+            options.unsafe_paths,
+            blocked_entries.iter().map(|entry| &entry.path).collect::<Vec<_>>()
+        ));
+        if options.unsafe_paths == UnsafePathPolicy::Refuse {
+            let mut paths: Vec<&str> = blocked_entries.iter().take(10).map(|entry| entry.path.as_str()).collect();
+            if blocked_entries.len() > paths.len() {
+                paths.push("...");
+            }
+            return Err(SoarZipError::UnsafePaths(format!(
+                "{} entries would be written outside the output directory: {}",
+                blocked_entries.len(),
+                paths.join(", ")
+            )));
+        }
+    }
+
+    // Where each entry ends up, once the path mode is applied
+    // 应用路径模式后每个条目的最终位置
+    let planned = plan_entries(&selected, &blocked_entries, options.path_mode, &options.base_path)?;
+
+    // "Extract here" decides between the chosen folder and a subfolder named after the archive
+    // "解压到此处"会在所选文件夹和以压缩包命名的子文件夹之间作出选择
+    let output_directory = match options.layout {
+        ExtractLayout::Direct => output_directory,
+        ExtractLayout::Smart => {
+            let directory = smart_output_directory(Path::new(&output_directory), Path::new(&archive_path), &planned);
+            log_info(&format!("Smart extraction chose output directory: {}", directory.display()));
+            directory.to_string_lossy().into_owned()
+        }
+    };
+
+    // Decompression bombs are refused before anything is written
+    // 在写入任何内容之前拒绝解压炸弹
+    check_extraction_limits(&selected, &options.limits)?;
+    check_disk_space(Path::new(&output_directory), total_bytes, options.disk_space_margin)?;
+
+    // In ask mode, existing files are reported back before anything is written
+    // 在询问模式下，在写入任何内容之前将已存在的文件报告回去
+    if options.overwrite == OverwritePolicy::Ask {
+        let conflicts = find_conflicts(Path::new(&output_directory), &planned);
+        if !conflicts.is_empty() {
+            log_info(&format!("Extraction stopped for confirmation, {} entries already exist: {:?}", conflicts.len(), conflicts));
+            return Ok(ExtractionReport { output_directory, blocked_entries, conflicts });
+        }
+    }
+
+    // Check if output directory exists, create if not
+    let output_path = Path::new(&output_directory);
+    if !output_path.exists() {
+        log_info(&format!("Output directory does not exist, attempting to create: {}", output_directory));
+        if let Err(e) = std::fs::create_dir_all(output_path) {
+            let error_msg = format!("Failed to create output directory '{}': {}", output_directory, e);
+            log_error(&error_msg);
+            return Err(SoarZipError::Io(error_msg));
+        }
+        log_info(&format!("Successfully created output directory: {}", output_directory));
+    } else if !output_path.is_dir() {
+         // Ensure the output path is actually a directory
+         let error_msg = format!("Output path exists but is not a directory: {}", output_directory);
+         log_error(&error_msg);
+         return Err(SoarZipError::InvalidInput(error_msg));
+    }
+
+    let job = app_handle.state::<JobRegistry>().register(job_id)?;
+    let mut reporter = ProgressReporter::for_frontend(app_handle, job.handle().id(), "extract", total_bytes);
+
+    // Relative paths are extracted in full into a staging folder first, then the base folder's
+    // contents are moved up; 7-Zip itself cannot strip a leading path
+    // 相对路径先以完整路径解压到暂存文件夹中，再将基准文件夹的内容上移；7-Zip 本身无法去掉前导路径
+    let staging = match options.path_mode {
+        PathMode::Relative => Some(StagingDirectory::create(output_path)?),
+        PathMode::Full | PathMode::Flatten => None,
+    };
+
+    // The sizes in the listing may be forged, so the written data is measured as well
+    // 列表中的大小可能是伪造的，因此还会测量实际写入的数据
+    let watchdog = options.limits.max_total_size.map(|max_total_size| {
+        let files = planned.iter().filter(|entry| !entry.individual && !entry.item.is_dir);
+        let targets: Vec<String> = match &staging {
+            // The staging folder holds the stored paths in full / 暂存文件夹中保存的是完整的存储路径
+            Some(staging) => files.map(|entry| format!("{}/{}", staging.name(), entry.item.name)).collect(),
+            None => files.map(|entry| entry.target.clone()).collect(),
+        };
+        OutputSizeWatchdog::start(output_path, &targets, max_total_size, job.handle().clone())
+    });
+
+    // Files that are already there are never removed by the cleanup after a cancelled run
+    // 已存在的文件在解压被取消后的清理中永远不会被删除
+    let existing_targets: HashSet<PathBuf> = match &staging {
+        Some(_) => HashSet::new(),
+        None => planned
+            .iter()
+            .filter(|entry| !entry.individual && !entry.item.is_dir)
+            .map(|entry| output_path.join(&entry.target))
+            .filter(|path| path.exists())
+            .collect(),
+    };
+
+    // Execute the 7-Zip extraction command, streaming progress to the frontend
+    let result = match options.path_mode {
+        PathMode::Full | PathMode::Relative => {
+            // Build 7-Zip command arguments
+            // Base command: 7z x <archive_path> -o<output_directory> -ao<mode> [files_to_extract...]
+            // 'x': Extract files with full paths
+            // '-o': Specify output directory (no space after -o)
+            // 'files_to_extract...': Optional list of files/dirs to extract (relative paths)
+            // '-ao<mode>': Overwrite mode from the policy (a: all, s: skip, u: rename extracted, t: rename existing);
+            //              the staging folder is empty, so the policy is applied when moving out of it instead
+            // '-x!': Exclude the entries with unsafe paths, which are handled separately below
+            let (extract_root, overwrite_switch) = match &staging {
+                Some(staging) => (staging.path(), OverwritePolicy::OverwriteAll.switch()),
+                None => (output_path, options.overwrite.switch()),
+            };
+            let mut args = vec![
+                "x".to_string(),                // Extract command
+                archive_path.clone(),       // Archive path
+                format!("-o{}", extract_root.display()), // Output directory (no space!)
+                overwrite_switch.to_string(), // Overwrite mode, never prompts
+            ];
+            args.extend(password_switch(password.as_deref())); // Never logged: see redact_args
+            for entry in &blocked_entries {
+                args.push(format!("-x!{}", entry.path));
+            }
+
+            // Add specific files/folders to the arguments if provided, after '--' so that an entry
+            // named like a switch (e.g. '-o/some/dir') is never parsed as one
+            // 7-Zip generally handles '/' separators well, even on Windows
+            args.push("--".to_string());
+            args.extend(files_to_extract.iter().cloned());
+            run_7z_with_progress(&seven_zip_path, &args, None, &mut reporter, job.handle()).map(Some)
+        }
+        PathMode::Flatten => {
+            // 'e' drops the stored folders; the planned files are listed one by one, since a folder
+            // name would pull in everything below it, including renamed duplicates
+            // 'e' 会去掉存储的文件夹；计划中的文件被逐一列出，因为文件夹名称会包含其下的所有内容，包括被重命名的重名文件
+            let names: Vec<String> = planned.iter().filter(|entry| !entry.individual).map(|entry| entry.item.name.clone()).collect();
+            if names.is_empty() {
+                // An empty list would extract the whole archive / 空列表会解压整个压缩包
+                Ok(None)
+            } else {
+                run_with_list_file(&names, |list_file_arg| {
+                    let mut args = vec![
+                        "e".to_string(),
+                        archive_path.clone(),
+                        format!("-o{}", output_directory),
+                        options.overwrite.switch().to_string(),
+                        "-scsUTF-8".to_string(),
+                        "-spd".to_string(), // The listed names are not wildcards / 列出的名称不是通配符
+                    ];
+                    args.extend(password_switch(password.as_deref()));
+                    args.push(list_file_arg.to_string());
+                    run_7z_with_progress(&seven_zip_path, &args, None, &mut reporter, job.handle())
+                })
+                .map(Some)
+            }
+        }
+    };
+    drop(watchdog);
+    let output = match result {
+        Ok(output) => output,
+        Err(e) => {
+            // A staging folder is removed as a whole when dropped / 暂存文件夹在被丢弃时会被整体删除
+            if matches!(e, SoarZipError::Cancelled | SoarZipError::LimitExceeded(_)) && staging.is_none() {
+                let written: Vec<String> = match options.path_mode {
+                    PathMode::Flatten => reporter
+                        .processed_files()
+                        .iter()
+                        .map(|name| name.rsplit(['/', '\\']).next().unwrap_or_default().to_string())
+                        .collect(),
+                    PathMode::Full | PathMode::Relative => reporter.processed_files().to_vec(),
+                };
+                // Only '-aot' moves an existing file aside before writing under its name; with the other
+                // policies a path that existed before still holds (or replaced) the user's file. The names
+                // '-aou' picks for conflicting files are unknown, so those partial files are left behind
+                // 只有 '-aot' 会在以原名称写入前将已有文件移开；使用其他策略时，原本存在的路径仍保存（或已替换）用户的文件。
+                // '-aou' 为冲突文件选择的名称未知，因此这些不完整的文件会被保留
+                let written: Vec<String> = written
+                    .into_iter()
+                    .filter(|name| {
+                        options.overwrite == OverwritePolicy::RenameExisting
+                            || !existing_targets.contains(&output_path.join(name))
+                    })
+                    .collect();
+                remove_partial_extraction(output_path, &written);
+            }
+            return Err(e);
+        }
+    };
+
+    // Check the result of the 7-Zip command; a wrong or missing password maps to PASSWORD_REQUIRED
+    let stdout_output = match &output {
+        Some(output) => check_7z_output(output, "extract")?.stdout,
+        None => String::new(),
+    };
+
+    if let Some(staging) = &staging {
+        let staged_base = staging.path().join(options.base_path.trim_matches('/'));
+        if staged_base.is_dir() {
+            merge_directory(&staged_base, output_path, options.overwrite)?;
+        }
+    }
+
+    // Sanitized entries and renamed duplicates are written one by one under their planned path
+    // 已清理的条目和被重命名的重名文件以其计划路径逐个写入
+    for entry in planned.iter().filter(|entry| entry.individual) {
+        let destination = output_path.join(&entry.target);
+        if entry.item.is_dir {
+            std::fs::create_dir_all(&destination).map_err(|e| {
+                SoarZipError::Io(format!("Failed to create directory '{}': {}", destination.display(), e))
+            })?;
+        } else if let Some(destination) = resolve_destination(destination, options.overwrite)? {
+            extract_entry_to_file(&seven_zip_path, &archive_path, &entry.item.name, password.as_deref(), &destination)?;
+        }
+    }
+    drop(staging);
+
+    reporter.finish();
+
+    // Log success and potentially some output
+    log_info("Bundled 7-Zip extract command executed successfully.");
+    if !stdout_output.is_empty() {
+         if stdout_output.len() < 500 { // Log short output fully
+             log_info(&format!("7-Zip output: {}", stdout_output.trim()));
+         } else { // Log length for long output
+             log_info(&format!("7-Zip output length: {}", stdout_output.len()));
+         }
+    } else {
+        log_info("7-Zip produced no output on stdout.");
+    }
+
+    Ok(ExtractionReport { output_directory, blocked_entries, conflicts: Vec::new() })
+}
+
+/// Creates a new archive. Blocking implementation of [`crate::commands::create_archive`].
+/// 创建新的压缩包。[`crate::commands::create_archive`] 的阻塞实现。
+pub fn create_archive(
+    app_handle: &AppHandle,
+    source_paths: Vec<String>,
+    archive_path: String,
+    format: ArchiveFormat,
+    relative_root: Option<String>,
+    encryption: Option<EncryptionOptions>,
+    job_id: Option<String>,
+) -> Result<CreatedArchive, SoarZipError> {
+    log_info(&format!(
+        "Creating {:?} archive: {}, Sources: {:?}, Relative root: {:?}, Encrypted: {}",
+        format, archive_path, source_paths, relative_root, encryption.is_some()
+    ));
+
+    let seven_zip_path = resolve_7z_path(app_handle)?;
+    let job = app_handle.state::<JobRegistry>().register(job_id)?;
+    let mut reporter = ProgressReporter::for_frontend(app_handle, job.handle().id(), "compress", total_source_size(&source_paths));
+    let (created_path, warnings) = create_archive_with_7z(
+        &seven_zip_path,
+        &source_paths,
+        Path::new(&archive_path),
+        format,
+        relative_root.as_deref().map(Path::new),
+        encryption.as_ref(),
+        &mut reporter,
+        job.handle(),
+    )?;
+
+    let archive_size = std::fs::metadata(&created_path)
+        .map(|m| m.len())
+        .map_err(|e| SoarZipError::Io(format!("Failed to read created archive '{}': {}", created_path.display(), e)))?;
+
+    Ok(CreatedArchive {
+        archive_path: created_path.to_string_lossy().to_string(),
+        format,
+        archive_size,
+        encrypted: encryption.is_some(),
+        warnings,
+    })
+}
+
+/// Adds files to an archive. Blocking implementation of [`crate::commands::add_to_archive`].
+/// 向压缩包添加文件。[`crate::commands::add_to_archive`] 的阻塞实现。
+pub fn add_to_archive(
+    app_handle: &AppHandle,
+    archive_path: String,
+    target_dir: String,
+    source_paths: Vec<String>,
+    conflict_policy: AddConflictPolicy,
+) -> Result<Vec<FileItem>, SoarZipError> {
+    log_info(&format!(
+        "Adding to archive: {}, Target dir: '{}', Sources: {:?}, Policy: {:?}",
+        archive_path, target_dir, source_paths, conflict_policy
+    ));
+
+    ensure_not_nested(&archive_path)?;
+    if !Path::new(&archive_path).exists() {
+        let error_msg = format!("Archive file not found: {}", archive_path);
+        log_error(&error_msg);
+        return Err(SoarZipError::ArchiveNotFound(error_msg));
+    }
+
+    // Edits of the same archive run one after another / 对同一压缩包的编辑依次执行
+    let archive_lock = app_handle.state::<ArchiveLocks>().lock_for(Path::new(&archive_path));
+    let _guard = archive_lock.lock().unwrap();
+    let seven_zip_path = resolve_7z_path(app_handle)?;
+    let existing_entries = list_archive_entries(&seven_zip_path, &archive_path, None)?;
+    add_to_archive_with_7z(
+        &seven_zip_path,
+        Path::new(&archive_path),
+        &existing_entries,
+        &target_dir,
+        &source_paths,
+        conflict_policy,
+    )?;
+
+    list_archive_entries(&seven_zip_path, &archive_path, None)
+}
+
+/// Deletes entries from an archive. Blocking implementation of [`crate::commands::delete_files_in_archive`].
+/// 从压缩包中删除条目。[`crate::commands::delete_files_in_archive`] 的阻塞实现。
+pub fn delete_files_in_archive(
+    app_handle: &AppHandle,
+    archive_path: String,
+    files: Vec<String>,
+) -> Result<Vec<FileItem>, SoarZipError> {
+    log_info(&format!("Deleting from archive: {}, Entries: {:?}", archive_path, files));
+
+    ensure_not_nested(&archive_path)?;
+    if !Path::new(&archive_path).exists() {
+        let error_msg = format!("Archive file not found: {}", archive_path);
+        log_error(&error_msg);
+        return Err(SoarZipError::ArchiveNotFound(error_msg));
+    }
+
+    // Edits of the same archive run one after another / 对同一压缩包的编辑依次执行
+    let archive_lock = app_handle.state::<ArchiveLocks>().lock_for(Path::new(&archive_path));
+    let _guard = archive_lock.lock().unwrap();
+    let seven_zip_path = resolve_7z_path(app_handle)?;
+    let existing_entries = list_archive_entries(&seven_zip_path, &archive_path, None)?;
+    delete_from_archive_with_7z(&seven_zip_path, Path::new(&archive_path), &existing_entries, &files)?;
+
+    list_archive_entries(&seven_zip_path, &archive_path, None)
+}
+
+/// Renames an entry in an archive. Blocking implementation of [`crate::commands::rename_in_archive`].
+/// 重命名压缩包内的条目。[`crate::commands::rename_in_archive`] 的阻塞实现。
+pub fn rename_in_archive(
+    app_handle: &AppHandle,
+    archive_path: String,
+    old_path: String,
+    new_path: String,
+) -> Result<Vec<FileItem>, SoarZipError> {
+    log_info(&format!("Renaming in archive: {}, '{}' -> '{}'", archive_path, old_path, new_path));
+
+    ensure_not_nested(&archive_path)?;
+    if !Path::new(&archive_path).exists() {
+        let error_msg = format!("Archive file not found: {}", archive_path);
+        log_error(&error_msg);
+        return Err(SoarZipError::ArchiveNotFound(error_msg));
+    }
+
+    // Edits of the same archive run one after another / 对同一压缩包的编辑依次执行
+    let archive_lock = app_handle.state::<ArchiveLocks>().lock_for(Path::new(&archive_path));
+    let _guard = archive_lock.lock().unwrap();
+    let seven_zip_path = resolve_7z_path(app_handle)?;
+    let existing_entries = list_archive_entries(&seven_zip_path, &archive_path, None)?;
+    rename_in_archive_with_7z(&seven_zip_path, Path::new(&archive_path), &existing_entries, &old_path, &new_path)?;
+
+    list_archive_entries(&seven_zip_path, &archive_path, None)
+}
+
+/// Tests an archive's integrity. Blocking implementation of [`crate::commands::test_archive`].
+/// 测试压缩包的完整性。[`crate::commands::test_archive`] 的阻塞实现。
+pub fn test_archive(
+    app_handle: &AppHandle,
+    archive_path: String,
+    password: Option<String>,
+    job_id: Option<String>,
+) -> Result<ArchiveTestReport, SoarZipError> {
+    log_info(&format!("Testing archive: {}", archive_path));
+
+    let seven_zip_path = resolve_7z_path(app_handle)?;
+    let archive_path = resolve_archive_path(&seven_zip_path, &app_handle.state::<TempFileRegistry>(), &archive_path, password.as_deref(), &ExtractionLimits::default())?
+        .to_string_lossy()
+        .into_owned();
+    let total_bytes = std::fs::metadata(&archive_path).map(|m| m.len()).unwrap_or(0);
+    let job = app_handle.state::<JobRegistry>().register(job_id)?;
+    let mut reporter = ProgressReporter::for_frontend(app_handle, job.handle().id(), "test", total_bytes);
+    test_archive_with_7z(&seven_zip_path, &archive_path, password.as_deref(), &mut reporter, job.handle())
+}
+
+/// Reads an entry for a preview. Blocking implementation of [`crate::commands::read_entry`].
+/// 读取条目以供预览。[`crate::commands::read_entry`] 的阻塞实现。
+pub fn read_entry(
+    app_handle: &AppHandle,
+    archive_path: String,
+    entry_path: String,
+    password: Option<String>,
+    max_bytes: Option<u64>,
+) -> Result<EntryPreview, SoarZipError> {
+    log_info(&format!("Reading entry for preview: '{}' in {}", entry_path, archive_path));
+
+    let seven_zip_path = resolve_7z_path(app_handle)?;
+    let archive_path = resolve_archive_path(&seven_zip_path, &app_handle.state::<TempFileRegistry>(), &archive_path, password.as_deref(), &ExtractionLimits::default())?
+        .to_string_lossy()
+        .into_owned();
+    let max_bytes = max_bytes.unwrap_or(DEFAULT_PREVIEW_MAX_BYTES);
+    let (bytes, truncated) = read_entry_bytes(&seven_zip_path, &archive_path, &entry_path, password.as_deref(), max_bytes)?;
+    Ok(build_preview(&entry_path, &bytes, truncated))
+}
+
+/// Searches the content of archive entries. Blocking implementation of [`crate::commands::search_archive_contents`].
+/// 搜索压缩包条目的内容。[`crate::commands::search_archive_contents`] 的阻塞实现。
+pub fn search_archive_contents(
+    app_handle: &AppHandle,
+    archive_path: String,
+    pattern: String,
+    password: Option<String>,
+    options: SearchOptions,
+    job_id: Option<String>,
+) -> Result<SearchReport, SoarZipError> {
+    log_info(&format!("Searching contents of {} (regex: {}, paths: {:?})", archive_path, options.regex, options.paths));
+
+    let matcher = build_matcher(&pattern, &options)?;
+    let seven_zip_path = resolve_7z_path(app_handle)?;
+    let archive_path = resolve_archive_path(&seven_zip_path, &app_handle.state::<TempFileRegistry>(), &archive_path, password.as_deref(), &ExtractionLimits::default())?
+        .to_string_lossy()
+        .into_owned();
+
+    let opened = list_archive(&seven_zip_path, &archive_path, password.as_deref())?;
+    let selected = select_entries(&opened.entries, &options.paths);
+    let job = app_handle.state::<JobRegistry>().register(job_id)?;
+    search_entries(
+        &seven_zip_path,
+        &archive_path,
+        &selected,
+        password.as_deref(),
+        opened.info.solid,
+        &matcher,
+        &options,
+        job.handle(),
+        |found| {
+            if let Err(e) = app_handle.emit(SEARCH_MATCH_EVENT, &found) {
+                log_error(&format!("Failed to emit search match event: {}", e));
+            }
+        },
+    )
+}
+
+/// Extracts an entry to the session's temporary directory and opens it with its default application.
+/// Blocking implementation of [`crate::commands::open_entry`].
+///
+/// 将条目解压到会话的临时目录，并用其默认应用程序打开。[`crate::commands::open_entry`] 的阻塞实现。
+pub fn open_entry(
+    app_handle: &AppHandle,
+    archive_path: String,
+    entry_path: String,
+    password: Option<String>,
+) -> Result<String, SoarZipError> {
+    log_info(&format!("Opening entry '{}' of {}", entry_path, archive_path));
+
+    // An entry opened before is opened again from the same file, keeping any changes made to it
+    // 之前打开过的条目会从同一文件再次打开，保留对它所做的任何更改
+    let registry = app_handle.state::<TempFileRegistry>();
+    let temp_path = match registry.find(&archive_path, &entry_path) {
+        Some(file) => file.path,
+        None => {
+            let seven_zip_path = resolve_7z_path(app_handle)?;
+            let archive_file = resolve_archive_path(&seven_zip_path, &registry, &archive_path, password.as_deref(), &ExtractionLimits::default())?;
+            let temp_path = registry.allocate(&entry_path)?;
+            extract_entry_to_file(&seven_zip_path, &archive_file.to_string_lossy(), &entry_path, password.as_deref(), &temp_path)?;
+            registry.register(TempFile { archive_path, entry_path, path: temp_path.clone() });
+            temp_path
+        }
+    };
+
+    let temp_path = temp_path.to_string_lossy().into_owned();
+    app_handle.opener().open_path(temp_path.as_str(), None::<&str>).map_err(|e| {
+        let error_msg = format!("Failed to open '{}' with its default application: {}", temp_path, e);
+        log_error(&error_msg);
+        SoarZipError::Io(error_msg)
+    })?;
+    Ok(temp_path)
+}
+
+/// Writes an opened entry's temporary file back into the archive.
+/// Blocking implementation of [`crate::commands::update_entry`].
+///
+/// 将已打开条目的临时文件写回压缩包。[`crate::commands::update_entry`] 的阻塞实现。
+pub fn update_entry(
+    app_handle: &AppHandle,
+    archive_path: String,
+    entry_path: String,
+    password: Option<String>,
+) -> Result<Vec<FileItem>, SoarZipError> {
+    log_info(&format!("Updating entry '{}' of {} from its temporary file", entry_path, archive_path));
+
+    ensure_not_nested(&archive_path)?;
+    if !Path::new(&archive_path).exists() {
+        let error_msg = format!("Archive file not found: {}", archive_path);
+        log_error(&error_msg);
+        return Err(SoarZipError::ArchiveNotFound(error_msg));
+    }
+
+    let registry = app_handle.state::<TempFileRegistry>();
+    let temp_file = registry.find(&archive_path, &entry_path).ok_or_else(|| {
+        SoarZipError::InvalidInput(format!("Entry '{}' has not been opened from {}", entry_path, archive_path))
+    })?;
+
+    // Edits of the same archive run one after another / 对同一压缩包的编辑依次执行
+    let archive_lock = app_handle.state::<ArchiveLocks>().lock_for(Path::new(&archive_path));
+    let _guard = archive_lock.lock().unwrap();
+    let seven_zip_path = resolve_7z_path(app_handle)?;
+    update_entry_with_7z(&seven_zip_path, Path::new(&archive_path), &entry_path, &temp_file.path, password.as_deref())?;
+    registry.mark_saved(&archive_path, &entry_path);
+
+    list_archive_entries(&seven_zip_path, &archive_path, password.as_deref())
+}
+
+/// Removes the files a cancelled extraction created, then any directories that were left
+/// empty by that. The caller leaves out files that existed before the extraction started.
+///
+/// 删除被取消的解压所创建的文件，然后删除因此变空的目录。调用方会排除解压开始前已存在的文件。
+fn remove_partial_extraction(output_path: &Path, processed_files: &[String]) {
+    let mut parent_dirs = Vec::new();
+    for name in processed_files {
+        let path = output_path.join(name);
+        if path.is_file() {
+            if let Err(e) = std::fs::remove_file(&path) {
+                log_error(&format!("Failed to remove partially extracted file '{}': {}", path.display(), e));
+            }
+        }
+        let mut parent = path.parent();
+        while let Some(dir) = parent {
+            if dir == output_path || !dir.starts_with(output_path) {
+                break;
+            }
+            parent_dirs.push(dir.to_path_buf());
+            parent = dir.parent();
+        }
+    }
+    // Deepest directories first so nested empty folders collapse completely
+    // 先处理最深的目录，使嵌套的空文件夹能被完全删除
+    parent_dirs.sort();
+    parent_dirs.dedup();
+    parent_dirs.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+    for dir in parent_dirs {
+        // remove_dir only succeeds on empty directories / remove_dir 仅在目录为空时成功
+        let _ = std::fs::remove_dir(&dir);
+    }
+    log_info(&format!("Cleaned up {} partially extracted entries in {}", processed_files.len(), output_path.display()));
+}
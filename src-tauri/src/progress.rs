@@ -0,0 +1,358 @@
+//! Progress reporting for long-running 7-Zip operations.
+//! 长时间运行的 7-Zip 操作的进度报告。
+
+use std::io::Read;
+use std::path::Path;
+use std::process::{Output, Stdio};
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use super::logging::{log_info, log_error};
+use super::archive_utils::{new_7z_command, decode_7z_output, redact_args, spawn_error};
+use super::jobs::JobHandle;
+use super::error::SoarZipError;
+
+/// Name of the Tauri event that carries [`ProgressUpdate`] payloads to the frontend.
+/// 将 [`ProgressUpdate`] 负载传递给前端的 Tauri 事件名称。
+pub const PROGRESS_EVENT: &str = "archive-progress";
+
+/// A single progress notification for a running archive operation.
+/// 正在运行的压缩包操作的单条进度通知。
+#[derive(Debug, Serialize, Clone)]
+pub struct ProgressUpdate {
+    /// The id of the job, usable with `cancel_operation`.
+    pub job_id: String,
+    /// The kind of operation, e.g. "extract" or "compress".
+    pub operation: String,
+    /// Overall completion from 0 to 100.
+    pub percent: u8,
+    /// The entry 7-Zip is currently working on, if known.
+    pub current_file: Option<String>,
+    /// Estimated number of bytes processed so far.
+    pub bytes_processed: u64,
+    /// Total number of bytes the operation is expected to process.
+    pub total_bytes: u64,
+    /// Estimated remaining time in seconds, once enough progress has been made to guess.
+    pub eta_seconds: Option<u64>,
+}
+
+/// Turns raw 7-Zip progress into [`ProgressUpdate`]s and hands them to a sink.
+///
+/// An operation may consist of several 7-Zip runs (e.g. tar, then gzip). Each run is a phase
+/// that is mapped onto a slice of the overall 0-100 range, so the reported percentage never
+/// jumps backwards.
+///
+/// 将 7-Zip 原始进度转换为 [`ProgressUpdate`] 并交给接收端。
+///
+/// 一个操作可能由多次 7-Zip 运行组成（例如先 tar 再 gzip）。每次运行是一个阶段，
+/// 映射到整体 0-100 范围中的一段，因此报告的百分比不会倒退。
+pub struct ProgressReporter {
+    job_id: String,
+    operation: String,
+    total_bytes: u64,
+    started: Instant,
+    phase_start: u8,
+    phase_span: u8,
+    last_percent: Option<u8>,
+    last_file: Option<String>,
+    processed_files: Vec<String>,
+    sink: Box<dyn Fn(&ProgressUpdate) + Send>,
+}
+
+impl ProgressReporter {
+    /// Creates a reporter that passes every update to `sink`.
+    ///
+    /// 创建一个将每条更新传给 `sink` 的报告器。
+    pub fn new(job_id: &str, operation: &str, total_bytes: u64, sink: Box<dyn Fn(&ProgressUpdate) + Send>) -> Self {
+        ProgressReporter {
+            job_id: job_id.to_string(),
+            operation: operation.to_string(),
+            total_bytes,
+            started: Instant::now(),
+            phase_start: 0,
+            phase_span: 100,
+            last_percent: None,
+            last_file: None,
+            processed_files: Vec::new(),
+            sink,
+        }
+    }
+
+    /// Creates a reporter that emits updates to the frontend as [`PROGRESS_EVENT`] events.
+    ///
+    /// 创建一个以 [`PROGRESS_EVENT`] 事件向前端发送更新的报告器。
+    pub fn for_frontend(app_handle: &AppHandle, job_id: &str, operation: &str, total_bytes: u64) -> Self {
+        let app_handle = app_handle.clone();
+        Self::new(job_id, operation, total_bytes, Box::new(move |update| {
+            if let Err(e) = app_handle.emit(PROGRESS_EVENT, update) {
+                log_error(&format!("Failed to emit progress event: {}", e));
+            }
+        }))
+    }
+
+    /// Maps the following 7-Zip run onto `span` percent of the overall progress, starting at `start`.
+    ///
+    /// 将接下来的 7-Zip 运行映射到整体进度中从 `start` 开始、占 `span` 个百分点的区间。
+    pub fn begin_phase(&mut self, start: u8, span: u8) {
+        self.phase_start = start.min(100);
+        self.phase_span = span.min(100 - self.phase_start);
+    }
+
+    /// Records a percentage (of the current phase) and/or the current file, emitting an update if either changed.
+    ///
+    /// 记录（当前阶段的）百分比和/或当前文件，若二者之一发生变化则发送更新。
+    fn record(&mut self, phase_percent: Option<u8>, current_file: Option<String>) {
+        let percent = match phase_percent {
+            Some(p) => self.phase_start + (u16::from(p.min(100)) * u16::from(self.phase_span) / 100) as u8,
+            None => self.last_percent.unwrap_or(self.phase_start),
+        };
+        let file_changed = current_file.is_some() && current_file != self.last_file;
+        if Some(percent) == self.last_percent && !file_changed {
+            return;
+        }
+        self.last_percent = Some(percent);
+        if let Some(file) = current_file {
+            if file_changed {
+                self.processed_files.push(file.clone());
+            }
+            self.last_file = Some(file);
+        }
+        self.emit(percent);
+    }
+
+    /// The entry names 7-Zip reported working on, in order. Used to clean up after cancellation.
+    ///
+    /// 7-Zip 报告处理过的条目名称（按顺序）。用于取消后的清理。
+    pub fn processed_files(&self) -> &[String] {
+        &self.processed_files
+    }
+
+    /// Emits a final 100% update once the operation has finished successfully.
+    ///
+    /// 在操作成功完成后发送最终的 100% 更新。
+    pub fn finish(&mut self) {
+        self.last_percent = Some(100);
+        self.emit(100);
+    }
+
+    fn emit(&self, percent: u8) {
+        let bytes_processed = self.total_bytes * u64::from(percent) / 100;
+        let elapsed = self.started.elapsed().as_secs_f64();
+        // Too early estimates swing wildly, so wait for a little progress first
+        // 过早的估计波动很大，因此先等待一些进度
+        let eta_seconds = if (1..100).contains(&percent) && elapsed >= 1.0 {
+            Some((elapsed * f64::from(100 - percent) / f64::from(percent)).round() as u64)
+        } else if percent >= 100 {
+            Some(0)
+        } else {
+            None
+        };
+        (self.sink)(&ProgressUpdate {
+            job_id: self.job_id.clone(),
+            operation: self.operation.clone(),
+            percent,
+            current_file: self.last_file.clone(),
+            bytes_processed,
+            total_bytes: self.total_bytes,
+            eta_seconds,
+        });
+    }
+}
+
+/// Runs a 7-Zip command with `-bsp1 -bb1` and reports progress while it runs.
+///
+/// 7-Zip redraws its progress indicator in place using `\r` and backspaces instead of
+/// newlines, so stdout is split on all three while it is being read.
+///
+/// # Arguments
+///
+/// * `seven_zip_path` - The path to the 7-Zip executable.
+/// * `args` - The 7-Zip arguments; the progress switches are inserted automatically.
+/// * `working_dir` - The directory to run 7-Zip from, or `None` to inherit the current one.
+/// * `reporter` - Receives the parsed progress.
+/// * `job` - The job the process belongs to; cancelling it kills the process.
+///
+/// # Returns
+///
+/// * `Ok(Output)` - The collected process output once 7-Zip has exited.
+/// * `Err(SoarZipError)` - `Cancelled` if the job was cancelled, the abort reason if it was
+///   aborted, or another error if the process could not be started or awaited.
+///
+/// 使用 `-bsp1 -bb1` 运行 7-Zip 命令，并在运行期间报告进度。
+///
+/// 7-Zip 使用 `\r` 和退格符而不是换行符原地重绘进度指示，因此读取 stdout 时会按这三者分割。
+///
+/// # 参数
+///
+/// * `seven_zip_path` - 7-Zip 可执行文件的路径。
+/// * `args` - 7-Zip 参数；进度开关会自动插入。
+/// * `working_dir` - 运行 7-Zip 的目录，为 `None` 时继承当前目录。
+/// * `reporter` - 接收解析后的进度。
+/// * `job` - 进程所属的作业；取消该作业会终止进程。
+///
+/// # 返回值
+///
+/// * `Ok(Output)` - 7-Zip 退出后收集到的进程输出。
+/// * `Err(SoarZipError)` - 如果作业被取消则返回 `Cancelled`；如果作业被中止则返回中止原因；
+///   如果进程无法启动或等待失败，则返回其他错误。
+pub fn run_7z_with_progress(
+    seven_zip_path: &Path,
+    args: &[String],
+    working_dir: Option<&Path>,
+    reporter: &mut ProgressReporter,
+    job: &JobHandle,
+) -> Result<Output, SoarZipError> {
+    if job.is_cancelled() {
+        return Err(job.stop_error());
+    }
+    let mut full_args = args.to_vec();
+    // Switches must come before a "--" terminator, so insert them right after the command name
+    // 开关必须位于 "--" 终止符之前，因此将其插入到命令名称之后
+    let insert_at = full_args.len().min(1);
+    full_args.splice(insert_at..insert_at, ["-bsp1".to_string(), "-bb1".to_string()]);
+    log_info(&format!("Executing 7-Zip command with progress: {:?} {:?} (cwd: {:?})", seven_zip_path, redact_args(&full_args), working_dir));
+
+    let mut command = new_7z_command(seven_zip_path, &full_args);
+    if let Some(dir) = working_dir {
+        command.current_dir(dir);
+    }
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(spawn_error)?;
+
+    // Drain stderr on its own thread so a full pipe can never block 7-Zip
+    // 在单独的线程中读取 stderr，避免管道写满阻塞 7-Zip
+    let stdout_pipe = child.stdout.take();
+    let mut stderr_pipe = child.stderr.take();
+    job.attach_child(child);
+
+    let stderr_thread = std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(pipe) = stderr_pipe.as_mut() {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        buffer
+    });
+
+    let mut stdout_bytes = Vec::new();
+    if let Some(mut stdout_pipe) = stdout_pipe {
+        let mut chunk = [0u8; 4096];
+        let mut segment = Vec::new();
+        loop {
+            let read = match stdout_pipe.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) => {
+                    log_error(&format!("Failed to read 7-Zip output: {}", e));
+                    break;
+                }
+            };
+            stdout_bytes.extend_from_slice(&chunk[..read]);
+            for &byte in &chunk[..read] {
+                if byte == b'\n' || byte == b'\r' || byte == 0x08 {
+                    handle_progress_segment(&segment, reporter);
+                    segment.clear();
+                } else {
+                    segment.push(byte);
+                }
+            }
+        }
+        handle_progress_segment(&segment, reporter);
+    }
+
+    // Poll instead of blocking in wait() so the job lock stays free for cancel_operation
+    // 轮询而不是阻塞在 wait() 中，使作业锁对 cancel_operation 保持可用
+    let status = loop {
+        if let Some(status) = job.try_wait_child()? {
+            break status;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    let stderr_bytes = stderr_thread.join().unwrap_or_default();
+
+    if job.is_cancelled() {
+        log_info(&format!("7-Zip process for job {} was cancelled", job.id()));
+        return Err(job.stop_error());
+    }
+    Ok(Output { status, stdout: stdout_bytes, stderr: stderr_bytes })
+}
+
+fn handle_progress_segment(segment: &[u8], reporter: &mut ProgressReporter) {
+    if segment.is_empty() {
+        return;
+    }
+    let line = decode_7z_output(segment);
+    let (percent, current_file) = parse_progress_line(&line);
+    if percent.is_some() || current_file.is_some() {
+        reporter.record(percent, current_file);
+    }
+}
+
+/// Parses one 7-Zip progress segment.
+///
+/// Recognized forms are `" 45% 12 - docs/a.txt"` (progress with the current file),
+/// `" 45%"` (progress only) and `"- docs/a.txt"` / `"+ docs/a.txt"` (file lines from `-bb1`).
+///
+/// 解析一段 7-Zip 进度输出。
+///
+/// 可识别的形式有 `" 45% 12 - docs/a.txt"`（带当前文件的进度）、`" 45%"`（仅进度）
+/// 以及 `"- docs/a.txt"` / `"+ docs/a.txt"`（来自 `-bb1` 的文件行）。
+fn parse_progress_line(line: &str) -> (Option<u8>, Option<String>) {
+    let line = line.trim();
+    let (percent, rest) = match line.find('%') {
+        Some(idx) => match line[..idx].trim().parse::<u8>() {
+            Ok(p) => (Some(p), line[idx + 1..].trim_start()),
+            Err(_) => (None, line),
+        },
+        None => (None, line),
+    };
+
+    // Skip the optional file counter before the marker / 跳过标记前可选的文件计数
+    let rest = rest.trim_start_matches(|c: char| c.is_ascii_digit()).trim_start();
+    let current_file = ["- ", "+ ", "U ", "T "]
+        .iter()
+        .find_map(|marker| rest.strip_prefix(marker))
+        .map(|name| name.trim().replace('\\', "/"))
+        .filter(|name| !name.is_empty());
+    (percent, current_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_progress_line;
+
+    #[test]
+    fn parses_percent_with_current_file() {
+        assert_eq!(parse_progress_line(" 45% 12 - docs/a.txt"), (Some(45), Some("docs/a.txt".to_string())));
+        assert_eq!(parse_progress_line("100% + archive.7z"), (Some(100), Some("archive.7z".to_string())));
+    }
+
+    #[test]
+    fn parses_percent_only() {
+        assert_eq!(parse_progress_line("  7%"), (Some(7), None));
+        assert_eq!(parse_progress_line(" 0% 3"), (Some(0), None));
+    }
+
+    #[test]
+    fn parses_file_lines_and_normalizes_separators() {
+        assert_eq!(parse_progress_line("- docs\\sub\\b.txt"), (None, Some("docs/sub/b.txt".to_string())));
+        assert_eq!(parse_progress_line("U notes.md"), (None, Some("notes.md".to_string())));
+        assert_eq!(parse_progress_line("T data.bin"), (None, Some("data.bin".to_string())));
+    }
+
+    #[test]
+    fn keeps_a_percent_sign_in_a_file_name() {
+        assert_eq!(parse_progress_line("- 5%.txt"), (None, Some("5%.txt".to_string())));
+    }
+
+    #[test]
+    fn ignores_other_output() {
+        assert_eq!(parse_progress_line("Everything is Ok"), (None, None));
+        assert_eq!(parse_progress_line("Extracting archive: a.7z"), (None, None));
+        assert_eq!(parse_progress_line(""), (None, None));
+        assert_eq!(parse_progress_line("- "), (None, None));
+    }
+}
@@ -0,0 +1,262 @@
+//! Entries extracted to a per-session temporary directory so other applications can open them,
+//! the watcher that notices when they are edited, and their cleanup when the archive is closed
+//! or the application exits.
+//!
+//! 解压到每个会话临时目录中、以便其他应用程序打开的条目，发现它们被编辑的监视器，
+//! 以及在压缩包关闭或应用程序退出时对它们的清理。
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use super::logging::{log_info, log_error};
+use super::archive_utils::create_temp_dir;
+use super::nested_archive::is_within;
+use super::error::SoarZipError;
+
+/// Name of the Tauri event that carries a [`TempFile`] whose content was changed by another application.
+/// 携带被其他应用程序修改了内容的 [`TempFile`] 的 Tauri 事件名称。
+pub const ENTRY_MODIFIED_EVENT: &str = "entry-modified";
+
+/// How often the watcher checks the temporary files / 监视器检查临时文件的间隔
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An archive entry extracted to a temporary file.
+/// 一个被解压到临时文件的压缩包条目。
+#[derive(Debug, Clone, Serialize)]
+pub struct TempFile {
+    /// The archive the entry belongs to.
+    pub archive_path: String,
+    /// The path of the entry inside the archive.
+    pub entry_path: String,
+    /// The temporary file.
+    pub path: PathBuf,
+}
+
+/// Tracks the temporary files of this session. Managed as Tauri state.
+///
+/// All files live below one session directory, each in its own numbered folder so entries with
+/// the same name do not collide while keeping their original file name (which decides the
+/// application that opens them).
+///
+/// 跟踪本会话的临时文件。作为 Tauri 状态进行管理。
+///
+/// 所有文件都位于同一个会话目录之下，每个文件都在自己的编号文件夹中，
+/// 这样同名条目不会冲突，同时保留原始文件名（它决定了用哪个应用程序打开文件）。
+#[derive(Default)]
+pub struct TempFileRegistry {
+    inner: Arc<Mutex<RegistryState>>,
+}
+
+#[derive(Default)]
+struct RegistryState {
+    session_dir: Option<PathBuf>,
+    next_id: u64,
+    files: Vec<TrackedFile>,
+}
+
+/// A temporary file together with what the watcher knows about its changes.
+/// 一个临时文件，以及监视器对其更改所掌握的信息。
+struct TrackedFile {
+    file: TempFile,
+    /// The state of the file when it matched the archive entry / 文件与压缩包条目一致时的状态
+    saved: Option<FileStamp>,
+    /// The state seen by the previous check / 上一次检查时看到的状态
+    last_seen: Option<FileStamp>,
+    /// The state the frontend was last told about / 上一次通知前端时的状态
+    notified: Option<FileStamp>,
+}
+
+/// Modification time and size, enough to tell that an editor saved a file.
+/// 修改时间和大小，足以判断编辑器是否保存了文件。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> Option<FileStamp> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(FileStamp { modified: metadata.modified().ok(), len: metadata.len() })
+    }
+}
+
+impl TrackedFile {
+    /// Takes the current state of the file into account and returns whether the frontend should
+    /// be told about a change. A change is only reported once the file has stayed the same for
+    /// a whole interval, so an editor that is still writing does not trigger it halfway.
+    ///
+    /// 记录文件的当前状态，并返回是否应通知前端发生了更改。只有当文件在一整个间隔内保持不变后才会报告更改，
+    /// 这样仍在写入的编辑器不会在写到一半时触发通知。
+    fn check(&mut self) -> bool {
+        let Some(current) = FileStamp::of(&self.file.path) else {
+            return false;
+        };
+        let settled = self.last_seen == Some(current);
+        self.last_seen = Some(current);
+        if !settled || self.saved == Some(current) || self.notified == Some(current) {
+            return false;
+        }
+        self.notified = Some(current);
+        true
+    }
+}
+
+impl TempFileRegistry {
+    /// Returns the temporary file of an entry if it was extracted before and still exists,
+    /// so that changes made to it by another application are kept.
+    ///
+    /// 如果条目之前已被解压且临时文件仍然存在，则返回该临时文件，以保留其他应用程序对它所做的更改。
+    pub fn find(&self, archive_path: &str, entry_path: &str) -> Option<TempFile> {
+        let state = self.inner.lock().unwrap();
+        state
+            .files
+            .iter()
+            .map(|tracked| &tracked.file)
+            .find(|file| file.archive_path == archive_path && file.entry_path == entry_path && file.path.is_file())
+            .cloned()
+    }
+
+    /// Reserves a path for extracting an entry: `<session>/<n>/<file name>`.
+    /// The session directory is created on first use.
+    ///
+    /// 为解压条目预留路径：`<会话目录>/<n>/<文件名>`。会话目录在首次使用时创建。
+    pub fn allocate(&self, entry_path: &str) -> Result<PathBuf, SoarZipError> {
+        let mut state = self.inner.lock().unwrap();
+        let session_dir = match &state.session_dir {
+            Some(dir) => dir.clone(),
+            None => {
+                let dir = create_temp_dir("session")?;
+                log_info(&format!("Created session directory for opened entries: {}", dir.display()));
+                state.session_dir = Some(dir.clone());
+                dir
+            }
+        };
+        state.next_id += 1;
+        Ok(session_dir.join(state.next_id.to_string()).join(temp_file_name(entry_path)))
+    }
+
+    /// Starts tracking an extracted entry / 开始跟踪一个已解压的条目
+    pub fn register(&self, file: TempFile) {
+        let saved = FileStamp::of(&file.path);
+        self.inner.lock().unwrap().files.push(TrackedFile { file, saved, last_seen: saved, notified: None });
+    }
+
+    /// Records that the archive entry was updated from its temporary file, so the current content
+    /// no longer counts as a change.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<TempFile>` - The temporary file, or `None` if the entry is not tracked.
+    ///
+    /// 记录压缩包条目已从其临时文件更新，因此当前内容不再算作更改。
+    ///
+    /// # 返回值
+    ///
+    /// * `Option<TempFile>` - 临时文件；如果该条目未被跟踪则为 `None`。
+    pub fn mark_saved(&self, archive_path: &str, entry_path: &str) -> Option<TempFile> {
+        let mut state = self.inner.lock().unwrap();
+        let tracked = state
+            .files
+            .iter_mut()
+            .find(|tracked| tracked.file.archive_path == archive_path && tracked.file.entry_path == entry_path)?;
+        tracked.saved = FileStamp::of(&tracked.file.path);
+        tracked.last_seen = tracked.saved;
+        tracked.notified = None;
+        Some(tracked.file.clone())
+    }
+
+    /// Starts a background thread that checks the temporary files every second and emits
+    /// [`ENTRY_MODIFIED_EVENT`] once for each saved change. Tauri has no file watching of its own,
+    /// and comparing modification time and size is enough for the handful of files opened per session.
+    ///
+    /// 启动一个后台线程，每秒检查一次临时文件，并为每次保存的更改发出一次 [`ENTRY_MODIFIED_EVENT`]。
+    /// Tauri 本身不提供文件监视，而对于每个会话打开的少量文件，比较修改时间和大小就足够了。
+    pub fn start_watcher(&self, app_handle: AppHandle) {
+        let inner = Arc::clone(&self.inner);
+        std::thread::spawn(move || loop {
+            std::thread::sleep(WATCH_INTERVAL);
+            let modified: Vec<TempFile> = {
+                let mut state = inner.lock().unwrap();
+                state.files.iter_mut().filter_map(|tracked| tracked.check().then(|| tracked.file.clone())).collect()
+            };
+            for file in modified {
+                log_info(&format!("Opened entry '{}' of {} was modified", file.entry_path, file.archive_path));
+                if let Err(e) = app_handle.emit(ENTRY_MODIFIED_EVENT, &file) {
+                    log_error(&format!("Failed to emit entry modified event: {}", e));
+                }
+            }
+        });
+    }
+
+    /// Deletes the temporary files of a closed archive, including those of archives nested in it.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The number of files that were tracked for the archive.
+    ///
+    /// 删除已关闭压缩包的临时文件，包括嵌套在其中的压缩包的临时文件。
+    ///
+    /// # 返回值
+    ///
+    /// * `usize` - 该压缩包被跟踪的文件数。
+    pub fn remove_archive(&self, archive_path: &str) -> usize {
+        let removed: Vec<TempFile> = {
+            let mut state = self.inner.lock().unwrap();
+            let (removed, kept): (Vec<TrackedFile>, Vec<TrackedFile>) =
+                state.files.drain(..).partition(|tracked| is_within(&tracked.file.archive_path, archive_path));
+            state.files = kept;
+            removed.into_iter().map(|tracked| tracked.file).collect()
+        };
+        for file in &removed {
+            // The numbered folder holds only this file / 编号文件夹中只有这一个文件
+            remove_path(file.path.parent().unwrap_or(&file.path));
+        }
+        if !removed.is_empty() {
+            log_info(&format!("Removed {} temporary files of closed archive: {}", removed.len(), archive_path));
+        }
+        removed.len()
+    }
+
+    /// Deletes the whole session directory. Called when the application exits.
+    ///
+    /// 删除整个会话目录。在应用程序退出时调用。
+    pub fn remove_all(&self) {
+        let mut state = self.inner.lock().unwrap();
+        state.files.clear();
+        if let Some(dir) = state.session_dir.take() {
+            remove_path(&dir);
+            log_info(&format!("Removed session directory: {}", dir.display()));
+        }
+    }
+}
+
+/// The file name an entry gets on disk: the last component of its path, with characters that
+/// Windows does not allow in file names replaced.
+///
+/// 条目在磁盘上的文件名：其路径的最后一个组成部分，并替换 Windows 不允许在文件名中使用的字符。
+fn temp_file_name(entry_path: &str) -> String {
+    let name = entry_path
+        .rsplit(['/', '\\'])
+        .find(|component| !component.is_empty() && *component != "." && *component != "..")
+        .unwrap_or("entry");
+    name.chars()
+        .map(|c| if matches!(c, '<' | '>' | ':' | '"' | '|' | '?' | '*') || c.is_control() { '_' } else { c })
+        .collect()
+}
+
+/// Removes a file or directory. A file still open in another application may not be deletable
+/// on Windows; that is logged and otherwise ignored.
+///
+/// 删除文件或目录。在 Windows 上，仍被其他应用程序打开的文件可能无法删除；这种情况会被记录，其余情况忽略。
+fn remove_path(path: &Path) {
+    let result = if path.is_dir() { std::fs::remove_dir_all(path) } else { std::fs::remove_file(path) };
+    if let Err(e) = result {
+        if path.exists() {
+            log_error(&format!("Failed to remove temporary path '{}': {}", path.display(), e));
+        }
+    }
+}
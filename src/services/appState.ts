@@ -1,4 +1,4 @@
-import { FileItem } from "./fileService";
+import { ArchiveInfo, FileItem } from "./fileService";
 
 /**
  * Holds the path of the currently opened archive file.
@@ -12,6 +12,12 @@ let currentArchivePath = "";
  */
 let currentFiles: FileItem[] = [];
 
+/**
+ * Properties of the current archive, shown in the properties panel.
+ * 当前压缩包的属性，显示在属性面板中。
+ */
+let currentArchiveInfo: ArchiveInfo | null = null;
+
 /**
  * Indicates whether the application is currently in a loading state (e.g., opening archive, extracting).
  * 指示应用程序当前是否处于加载状态（例如，打开压缩包、解压）。
@@ -62,6 +68,28 @@ export function setCurrentFiles(files: FileItem[]): void {
   currentFiles = files;
 }
 
+/**
+ * Gets the properties of the currently opened archive.
+ * 获取当前打开的压缩包的属性。
+ * 
+ * @returns - The archive properties, or null if no archive is open.
+ *          - 压缩包属性，如果未打开压缩包则为 null。
+ */
+export function getCurrentArchiveInfo(): ArchiveInfo | null {
+  return currentArchiveInfo;
+}
+
+/**
+ * Sets the properties of the currently opened archive.
+ * 设置当前打开的压缩包的属性。
+ * 
+ * @param info - The archive properties.
+ *             - 压缩包属性。
+ */
+export function setCurrentArchiveInfo(info: ArchiveInfo | null): void {
+  currentArchiveInfo = info;
+}
+
 /**
  * Gets the current loading state of the application.
  * 获取应用程序的当前加载状态。
@@ -91,5 +119,6 @@ export function setIsLoading(loading: boolean): void {
 export function resetAppState(): void {
   currentArchivePath = "";
   currentFiles = [];
+  currentArchiveInfo = null;
   isLoading = false; // Ensure loading is also reset
 } 
\ No newline at end of file
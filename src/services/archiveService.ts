@@ -1,6 +1,11 @@
 import {
+  FileItem,
   openArchive as invokeOpenArchive,
   selectArchiveFile as invokeSelectArchiveFile,
+  closeArchive as invokeCloseArchive,
+  PASSWORD_REQUIRED,
+  getErrorCode,
+  getErrorMessage,
 } from "./fileService";
 import { setWindowTitle, getFileNameFromPath } from "./windowService";
 import {
@@ -10,14 +15,28 @@ import { updateToolbarButtonsState } from "../setup/toolbar";
 import { showHomePage } from "../ui/uiManager";
 import { showFileBrowser } from "../ui/fileExplorer";
 import {
+  getCurrentArchivePath,
   setCurrentArchivePath,
   setCurrentFiles,
+  setCurrentArchiveInfo,
   resetAppState,
   setIsLoading,
 } from "./appState";
 import { navigationHistory } from "./navigationService";
 import { showError, showSuccess } from "../ui/notification";
 
+/**
+ * Separates an archive from the path of an archive stored inside it, e.g. `outer.7z!/inner/data.zip`.
+ * 分隔压缩包与其内部压缩包路径的分隔符，例如 `outer.7z!/inner/data.zip`。
+ */
+const NESTED_ARCHIVE_SEPARATOR = "!/";
+
+/**
+ * File extensions of entries that can be browsed as nested archives.
+ * 可以作为嵌套压缩包浏览的条目的文件扩展名。
+ */
+const NESTED_ARCHIVE_EXTENSIONS = ["zip", "7z", "rar", "tar", "gz", "tgz", "bz2", "xz", "iso", "cab"];
+
 
 /**
  * Opens a file dialog for selecting an archive file and loads it upon selection.
@@ -44,20 +63,27 @@ export async function openArchiveDialogAndLoad() {
  * Loads an archive file and updates the application state and UI.
  * 加载压缩包文件并更新应用程序状态和UI。
  *
- * @param archivePath - Path to the archive file to be loaded.
- *                    - 要加载的压缩包文件路径。
+ * @param archivePath - Path to the archive file to be loaded, or a virtual path to a nested archive.
+ *                    - 要加载的压缩包文件路径，或指向嵌套压缩包的虚拟路径。
+ * @param initialFolder - Folder inside the archive to show first (root by default).
+ *                      - 首先显示的压缩包内文件夹（默认为根目录）。
  */
-export async function loadArchive(archivePath: string) {
+export async function loadArchive(archivePath: string, initialFolder: string = "") {
   try {
     setIsLoading(true);
     console.log(`Starting to open archive: ${archivePath}`);
-    const files = await invokeOpenArchive(archivePath);
+    const { info, entries: files } = await invokeOpenArchive(archivePath);
     console.log(`Successfully retrieved file list with ${files.length} items`);
+    if (info.warnings.length > 0) {
+      console.warn(`7-Zip reported warnings for ${archivePath}:`, info.warnings);
+    }
 
+    releaseArchive(archiveToRelease(getCurrentArchivePath(), archivePath));
     setCurrentArchivePath(archivePath);
     setCurrentFiles(files);
+    setCurrentArchiveInfo(info);
 
-    navigationHistory.reset("");
+    navigationHistory.reset(initialFolder);
     showFileBrowser();
 
     const archiveFileName = getFileNameFromPath(archivePath);
@@ -74,11 +100,112 @@ export async function loadArchive(archivePath: string) {
 
   } catch (error) {
     console.error('Failed to open archive:', error);
-    showError(`打开压缩包失败: ${error}`);
+    if (getErrorCode(error) === PASSWORD_REQUIRED) {
+      showError("该压缩包已加密，需要密码才能打开");
+    } else {
+      showError(`打开压缩包失败: ${getErrorMessage(error)}`);
+    }
+    releaseArchive(getCurrentArchivePath());
     resetAppState();
     showHomePage();
     updateToolbarButtonsState(false);
   } finally {
     setIsLoading(false);
   }
-} 
\ No newline at end of file
+} 
+
+/**
+ * Deletes the temporary files of entries opened from an archive that is no longer shown.
+ * 删除从不再显示的压缩包中打开的条目的临时文件。
+ *
+ * @param archivePath - The archive being closed; nothing happens if it is empty.
+ *                    - 正在关闭的压缩包；为空时不执行任何操作。
+ */
+export function releaseArchive(archivePath: string) {
+  if (!archivePath) return;
+  invokeCloseArchive(archivePath)
+    .then((removed) => console.log(`Removed ${removed} temporary files of ${archivePath}`))
+    .catch((error) => console.error(`Failed to clean up temporary files of ${archivePath}:`, error));
+}
+
+
+/**
+ * Decides which archive's temporary files can be deleted when switching archives.
+ * Going into a nested archive keeps everything, since its file lives with the outer archive;
+ * leaving for an unrelated archive releases the whole chain from its outermost archive.
+ *
+ * 决定切换压缩包时可以删除哪个压缩包的临时文件。
+ * 进入嵌套压缩包时保留所有内容，因为它的文件归属于外层压缩包；
+ * 切换到无关的压缩包时，从最外层压缩包开始释放整条链。
+ *
+ * @param previousPath - The archive shown so far.
+ *                     - 之前显示的压缩包。
+ * @param nextPath - The archive about to be shown.
+ *                 - 即将显示的压缩包。
+ * @returns - The archive to release, or an empty string for none.
+ *          - 要释放的压缩包；为空字符串表示不释放。
+ */
+function archiveToRelease(previousPath: string, nextPath: string): string {
+  if (!previousPath || previousPath === nextPath) return "";
+  if (nextPath.startsWith(previousPath + NESTED_ARCHIVE_SEPARATOR)) return "";
+  const previousRoot = previousPath.split(NESTED_ARCHIVE_SEPARATOR)[0];
+  const nextRoot = nextPath.split(NESTED_ARCHIVE_SEPARATOR)[0];
+  return previousRoot === nextRoot ? previousPath : previousRoot;
+}
+
+/**
+ * Checks whether a path addresses an archive inside another archive.
+ * 检查路径是否指向另一个压缩包内的压缩包。
+ *
+ * @param archivePath - The archive path to check.
+ *                    - 要检查的压缩包路径。
+ */
+export function isNestedArchivePath(archivePath: string): boolean {
+  return archivePath.includes(NESTED_ARCHIVE_SEPARATOR);
+}
+
+/**
+ * Checks whether an entry can be browsed as a nested archive.
+ * 检查条目是否可以作为嵌套压缩包浏览。
+ *
+ * @param file - The entry to check.
+ *             - 要检查的条目。
+ */
+export function isArchiveEntry(file: FileItem): boolean {
+  if (file.is_dir) return false;
+  const extension = file.name.split('.').pop()?.toLowerCase() ?? "";
+  return NESTED_ARCHIVE_EXTENSIONS.includes(extension);
+}
+
+/**
+ * Opens an archive stored inside the current archive.
+ * 打开存储在当前压缩包内的压缩包。
+ *
+ * @param file - The archive entry to open.
+ *             - 要打开的压缩包条目。
+ */
+export async function openNestedArchive(file: FileItem) {
+  const currentArchivePath = getCurrentArchivePath();
+  if (!currentArchivePath) return;
+  await loadArchive(`${currentArchivePath}${NESTED_ARCHIVE_SEPARATOR}${file.name}`);
+}
+
+/**
+ * Goes back up from a nested archive to the archive containing it, showing the folder the nested archive is in.
+ * 从嵌套压缩包返回到包含它的压缩包，并显示嵌套压缩包所在的文件夹。
+ *
+ * @returns - False if the current archive is not nested.
+ *          - 如果当前压缩包不是嵌套的，则返回 false。
+ */
+export async function openParentArchive(): Promise<boolean> {
+  const currentArchivePath = getCurrentArchivePath();
+  const separatorIndex = currentArchivePath.lastIndexOf(NESTED_ARCHIVE_SEPARATOR);
+  if (separatorIndex === -1) return false;
+
+  const parentArchivePath = currentArchivePath.substring(0, separatorIndex);
+  const entryPath = currentArchivePath.substring(separatorIndex + NESTED_ARCHIVE_SEPARATOR.length);
+  const folderEnd = entryPath.lastIndexOf('/');
+  const folder = folderEnd === -1 ? "" : entryPath.substring(0, folderEnd + 1);
+  await loadArchive(parentArchivePath, folder);
+  return true;
+}
@@ -0,0 +1,102 @@
+import {
+  SearchMatch,
+  searchArchiveContents,
+  listenToSearchMatches,
+  cancelOperation,
+  getErrorCode,
+  getErrorMessage,
+  OPERATION_CANCELLED,
+  PASSWORD_REQUIRED,
+} from "./fileService";
+import { getCurrentArchivePath, getCurrentFiles } from "./appState";
+import { navigationHistory } from "./navigationService";
+import { previewEntry } from "./entryService";
+import {
+  showSearchDialog,
+  clearSearchResults,
+  appendSearchMatch,
+  setSearchStatus,
+} from "../ui/searchDialog";
+
+/**
+ * Id of the running content search, if any.
+ * 正在运行的内容搜索的 id（如果有）。
+ */
+let runningJobId: string | null = null;
+
+/**
+ * Opens the content search dialog for the current archive.
+ * 为当前压缩包打开内容搜索对话框。
+ *
+ * @param initialPattern - Text to prefill, e.g. from the name search box.
+ *                       - 预先填入的文本，例如来自文件名搜索框的内容。
+ */
+export function openContentSearch(initialPattern: string = "") {
+  if (!getCurrentArchivePath()) return;
+  showSearchDialog(initialPattern, {
+    onSearch: (pattern, regex, caseSensitive) => { void runContentSearch(pattern, regex, caseSensitive); },
+    onStop: () => {
+      if (runningJobId) void cancelOperation(runningJobId);
+    },
+    onResultClick: (match) => {
+      const file = getCurrentFiles().find((item) => item.name === match.entry_path);
+      if (file) void previewEntry(file);
+    },
+  });
+}
+
+/**
+ * Searches the files of the folder being browsed (the whole archive at its root),
+ * showing matches as they arrive.
+ * 搜索正在浏览的文件夹中的文件（位于根目录时搜索整个压缩包），并在匹配到达时显示。
+ *
+ * @param pattern - Text or regular expression to find.
+ *                - 要查找的文本或正则表达式。
+ * @param regex - Whether the pattern is a regular expression.
+ *              - 模式是否为正则表达式。
+ * @param caseSensitive - Whether case must match exactly.
+ *                      - 是否区分大小写。
+ */
+async function runContentSearch(pattern: string, regex: boolean, caseSensitive: boolean) {
+  const currentArchivePath = getCurrentArchivePath();
+  if (!currentArchivePath || runningJobId) return;
+
+  const jobId = `search-${Date.now()}`;
+  runningJobId = jobId;
+  let matchCount = 0;
+  const unlisten = await listenToSearchMatches((match: SearchMatch) => {
+    if (match.job_id !== jobId) return;
+    matchCount++;
+    appendSearchMatch(match);
+    setSearchStatus(`正在搜索... 已找到 ${matchCount} 处匹配`, true);
+  });
+
+  const currentFolder = navigationHistory.getCurrentPath();
+  try {
+    clearSearchResults();
+    setSearchStatus("正在搜索...", true);
+    const report = await searchArchiveContents(currentArchivePath, pattern, {
+      regex,
+      case_sensitive: caseSensitive,
+      paths: currentFolder ? [currentFolder] : [],
+    }, jobId);
+
+    const skipped = report.skipped_binary.length + report.skipped_too_large.length + report.failed.length;
+    let summary = `在 ${report.entries_searched} 个文件中找到 ${report.match_count} 处匹配（${report.entries_matched} 个文件）`;
+    if (skipped > 0) summary += `，跳过 ${skipped} 个二进制、过大或无法读取的文件`;
+    if (report.limit_reached) summary += `，已达到结果上限`;
+    setSearchStatus(summary, false);
+  } catch (error) {
+    if (getErrorCode(error) === OPERATION_CANCELLED) {
+      setSearchStatus(`搜索已停止，已找到 ${matchCount} 处匹配`, false);
+    } else if (getErrorCode(error) === PASSWORD_REQUIRED) {
+      setSearchStatus("该压缩包已加密，无法搜索文件内容", false);
+    } else {
+      console.error("Content search failed:", error);
+      setSearchStatus(`搜索失败: ${getErrorMessage(error)}`, false);
+    }
+  } finally {
+    unlisten();
+    runningJobId = null;
+  }
+}
@@ -0,0 +1,121 @@
+import {
+  FileItem,
+  ModifiedEntry,
+  readEntry,
+  openEntry,
+  updateEntry,
+  listenToEntryModified,
+  getErrorCode,
+  getErrorMessage,
+  PASSWORD_REQUIRED,
+} from "./fileService";
+import { getCurrentArchivePath, setCurrentFiles } from "./appState";
+import { isNestedArchivePath } from "./archiveService";
+import { showPreviewDialog } from "../ui/previewDialog";
+import { showConfirmDialog } from "../ui/confirmDialog";
+import { showError, showSuccess } from "../ui/notification";
+import { refreshUI, updateLoadingStatus } from "../ui/uiManager";
+
+/**
+ * Reads a file inside the archive and shows it in the preview dialog.
+ * 读取压缩包内的文件并在预览对话框中显示。
+ *
+ * @param file - The file to preview.
+ *             - 要预览的文件。
+ */
+export async function previewEntry(file: FileItem) {
+  const currentArchivePath = getCurrentArchivePath();
+  if (!currentArchivePath) return;
+
+  try {
+    updateLoadingStatus(true, `正在读取 ${file.name}...`);
+    const preview = await readEntry(currentArchivePath, file.name);
+    showPreviewDialog(file.name, preview);
+  } catch (error) {
+    console.error(`Failed to preview ${file.name}:`, error);
+    if (getErrorCode(error) === PASSWORD_REQUIRED) {
+      showError("该压缩包已加密，无法预览");
+    } else {
+      showError(`预览失败: ${getErrorMessage(error)}`);
+    }
+  } finally {
+    updateLoadingStatus(false);
+  }
+}
+
+/**
+ * Opens a file inside the archive with its default application.
+ * 用默认应用程序打开压缩包内的文件。
+ *
+ * The file is extracted to a temporary folder that is cleaned up when the archive is closed.
+ * 文件会被解压到临时文件夹，该文件夹会在压缩包关闭时被清理。
+ *
+ * @param file - The file to open.
+ *             - 要打开的文件。
+ */
+export async function openEntryWithDefaultApp(file: FileItem) {
+  const currentArchivePath = getCurrentArchivePath();
+  if (!currentArchivePath) return;
+
+  try {
+    updateLoadingStatus(true, `正在打开 ${file.name}...`);
+    const tempPath = await openEntry(currentArchivePath, file.name);
+    console.log(`Opened ${file.name} from temporary file: ${tempPath}`);
+  } catch (error) {
+    console.error(`Failed to open ${file.name}:`, error);
+    if (getErrorCode(error) === PASSWORD_REQUIRED) {
+      showError("该压缩包已加密，需要正确的密码才能打开文件");
+    } else {
+      showError(`打开文件失败: ${getErrorMessage(error)}`);
+    }
+  } finally {
+    updateLoadingStatus(false);
+  }
+}
+
+/**
+ * Offers to write an opened file back into the archive whenever it is saved in another application.
+ * Called once at startup.
+ * 每当已打开的文件在其他应用程序中被保存时，提示将其写回压缩包。在启动时调用一次。
+ */
+export async function setupEntryUpdates() {
+  await listenToEntryModified((entry) => {
+    // Files of an archive that is no longer shown are cleaned up, not written back
+    // 不再显示的压缩包的文件会被清理，而不是写回
+    if (entry.archive_path !== getCurrentArchivePath()) return;
+    // Archives inside other archives are read-only / 其他压缩包内的压缩包是只读的
+    if (isNestedArchivePath(entry.archive_path)) return;
+    showConfirmDialog(
+      `文件 ${entry.entry_path} 已修改，是否更新到压缩包？`,
+      () => { void updateModifiedEntry(entry); }
+    );
+  });
+}
+
+/**
+ * Writes a modified entry back into the archive and refreshes the listing.
+ * 将修改过的条目写回压缩包并刷新文件列表。
+ *
+ * @param entry - The entry that was modified.
+ *              - 被修改的条目。
+ */
+async function updateModifiedEntry(entry: ModifiedEntry) {
+  try {
+    updateLoadingStatus(true, `正在更新 ${entry.entry_path}...`);
+    const files = await updateEntry(entry.archive_path, entry.entry_path);
+    if (entry.archive_path === getCurrentArchivePath()) {
+      setCurrentFiles(files);
+      refreshUI();
+    }
+    showSuccess(`已将 ${entry.entry_path} 更新到压缩包`);
+  } catch (error) {
+    console.error(`Failed to update ${entry.entry_path}:`, error);
+    if (getErrorCode(error) === PASSWORD_REQUIRED) {
+      showError("该压缩包已加密，无法更新文件");
+    } else {
+      showError(`更新压缩包失败: ${getErrorMessage(error)}`);
+    }
+  } finally {
+    updateLoadingStatus(false);
+  }
+}
@@ -1,17 +1,28 @@
 import {
   selectDestinationFolder as invokeSelectDestinationFolder,
   extractFiles as invokeExtractFiles,
+  listenToArchiveProgress,
+  cancelOperation,
+  OPERATION_CANCELLED,
+  PASSWORD_REQUIRED,
+  UNSAFE_ENTRY_PATHS,
+  EXTRACTION_LIMIT_EXCEEDED,
+  DISK_FULL,
+  getErrorCode,
+  getErrorMessage,
+  OverwritePolicy,
+  PathMode,
 } from "./fileService";
 import { getDefaultExtractPath } from "./windowService";
 import { getSelectedFiles } from "../ui/fileExplorer"; // Assuming fileExplorer exports this
 import { showExtractDialog } from "../ui/extractDialog"; // Assuming extractDialog exports this
-import { showError, showSuccess } from "../ui/notification";
+import { showConflictDialog } from "../ui/conflictDialog";
+import { showError, showInfo, showSuccess, showWarning } from "../ui/notification";
 import {
   getCurrentArchivePath,
-  setIsLoading,
 } from "./appState";
-// Need a way to update loading status message, e.g., via uiManager
-// import { updateLoadingStatus } from '../ui/uiManager';
+import { updateLoadingStatus } from '../ui/uiManager';
+import { navigationHistory } from "./navigationService";
 
 /**
  * Starts the extraction process by showing the extract dialog.
@@ -50,9 +61,11 @@ export async function startExtractionProcess(filesToExtractOverride?: string[])
         return null;
       }
     },
-    (confirmedPath: string) => {
-      console.log(`Extraction confirmed to: ${confirmedPath}`);
-      performExtraction(confirmedPath, filesToExtract);
+    (confirmedPath: string, pathMode: PathMode) => {
+      console.log(`Extraction confirmed to: ${confirmedPath} (paths: ${pathMode})`);
+      // "relative" keeps the paths below the folder being browsed
+      // "relative" 保留正在浏览的文件夹之下的路径
+      performExtraction(confirmedPath, filesToExtract, pathMode, navigationHistory.getCurrentPath());
     },
     () => {
       console.log("Extraction operation cancelled.");
@@ -71,25 +84,115 @@ export async function startExtractionProcess(filesToExtractOverride?: string[])
  *                    - 目标文件夹路径
  * @param filesToExtract - Array of file paths to extract
  *                       - 要解压的文件路径数组
+ * @param pathMode - How the folder structure inside the archive is kept
+ *                 - 如何保留压缩包内的文件夹结构
+ * @param basePath - The folder "relative" paths start from
+ *                 - "relative" 路径起始的文件夹
+ * @param overwrite - How to handle existing files; "ask" shows the conflict dialog if any exist
+ *                  - 如何处理已存在的文件；"ask" 会在存在冲突时显示冲突对话框
  */
-async function performExtraction(destination: string, filesToExtract: string[]) {
+async function performExtraction(
+  destination: string,
+  filesToExtract: string[],
+  pathMode: PathMode,
+  basePath: string,
+  overwrite: OverwritePolicy = "ask",
+) {
   const currentArchivePath = getCurrentArchivePath();
+  let conflicts: string[] = [];
+  const jobId = `extract-${Date.now()}`;
+  const unlisten = await listenToArchiveProgress((update) => {
+    if (update.job_id !== jobId) return;
+    const file = update.current_file ? ` - ${update.current_file}` : "";
+    updateLoadingStatus(true, `正在解压 ${update.percent}%${file}`);
+  });
 
   try {
-    setIsLoading(true); // Update loading status via uiManager
-    // updateLoadingStatus(true, loadingMessage);
+    updateLoadingStatus(true, "正在解压...");
+    showCancelButton(() => cancelOperation(jobId));
     console.log(`Starting backend extraction: archive=${currentArchivePath}, files=${filesToExtract.length}, dest=${destination}`);
 
-    await invokeExtractFiles(currentArchivePath, filesToExtract, destination);
+    // "smart" adds a folder named after the archive only when it has several top-level entries
+    // "smart" 仅在压缩包有多个顶层条目时才添加以压缩包命名的文件夹
+    const report = await invokeExtractFiles(currentArchivePath, filesToExtract, destination, jobId, undefined, {
+      overwrite,
+      layout: "smart",
+      path_mode: pathMode,
+      base_path: basePath,
+    });
 
     console.log("Backend extraction command completed");
-    showSuccess(`文件已成功解压到: ${destination}`);
+    if (report.conflicts.length > 0) {
+      // Nothing was written yet; the user decides below once the loading state is cleared
+      // 尚未写入任何内容；加载状态清除后由用户在下方作出决定
+      console.log(`${report.conflicts.length} files already exist in the destination`);
+      conflicts = report.conflicts;
+    } else if (report.blocked_entries.length > 0) {
+      console.warn("Entries with unsafe paths were skipped or sanitized:", report.blocked_entries);
+      showWarning(`文件已解压到: ${report.output_directory}，但有 ${report.blocked_entries.length} 个路径不安全的条目未按原路径解压`);
+    } else {
+      showSuccess(`文件已成功解压到: ${report.output_directory}`);
+    }
 
   } catch (error) {
-    console.error("Error during extraction:", error);
-    showError(`解压失败: ${error}`);
+    if (getErrorCode(error) === OPERATION_CANCELLED) {
+      console.log("Extraction cancelled by user");
+      showInfo("解压已取消");
+    } else if (getErrorCode(error) === PASSWORD_REQUIRED) {
+      showError("该压缩包已加密，需要正确的密码才能解压");
+    } else if (getErrorCode(error) === UNSAFE_ENTRY_PATHS) {
+      console.error("Extraction refused:", error);
+      showError(`压缩包中包含会写到目标文件夹之外的条目，已拒绝解压: ${getErrorMessage(error)}`);
+    } else if (getErrorCode(error) === EXTRACTION_LIMIT_EXCEEDED) {
+      console.error("Extraction limit exceeded:", error);
+      showError(`压缩包超出安全限制（可能是解压炸弹），已停止解压: ${getErrorMessage(error)}`);
+    } else if (getErrorCode(error) === DISK_FULL) {
+      showError(`目标磁盘空间不足，无法解压: ${getErrorMessage(error)}`);
+    } else {
+      console.error("Error during extraction:", error);
+      showError(`解压失败: ${getErrorMessage(error)}`);
+    }
   } finally {
-    setIsLoading(false); // Update loading status via uiManager
-    // updateLoadingStatus(false);
+    unlisten();
+    hideCancelButton();
+    updateLoadingStatus(false);
+  }
+
+  if (conflicts.length > 0) {
+    const policy = await showConflictDialog(conflicts);
+    if (policy) {
+      await performExtraction(destination, filesToExtract, pathMode, basePath, policy);
+    } else {
+      console.log("Extraction cancelled at the conflict dialog.");
+    }
   }
-} 
\ No newline at end of file
+}
+
+/**
+ * Shows the status bar cancel button for the running operation.
+ * 为正在运行的操作显示状态栏中的取消按钮。
+ *
+ * @param onCancel - Called when the user clicks the button
+ *                 - 用户点击按钮时调用
+ */
+function showCancelButton(onCancel: () => void) {
+  const cancelButton = document.getElementById('status-cancel-btn');
+  if (!cancelButton) return;
+  cancelButton.onclick = () => {
+    cancelButton.setAttribute('disabled', 'true');
+    onCancel();
+  };
+  cancelButton.removeAttribute('disabled');
+  cancelButton.style.display = 'inline-block';
+}
+
+/**
+ * Hides the status bar cancel button.
+ * 隐藏状态栏中的取消按钮。
+ */
+function hideCancelButton() {
+  const cancelButton = document.getElementById('status-cancel-btn');
+  if (!cancelButton) return;
+  cancelButton.onclick = null;
+  cancelButton.style.display = 'none';
+}
@@ -3,6 +3,7 @@
  * 文件服务模块 - 处理所有文件和压缩包操作
  */
 import { invoke } from "@tauri-apps/api/core";
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
 
 /**
  * Interface representing a file or directory item within an archive
@@ -14,6 +15,41 @@ export interface FileItem {
   size: number;       // File size in bytes
   modified_date: string; // Last modified date as string
   type_name: string;  // File type description
+  packed_size: number | null;    // Compressed size in bytes, if reported per entry
+  crc: string | null;            // CRC32 checksum (hex)
+  method: string | null;         // Compression/encryption method, e.g. "LZMA2:24"
+  encrypted: boolean;            // Whether the entry is encrypted
+  attributes: string | null;     // File attributes or Unix permissions
+  created_date: string | null;   // Creation date, if stored
+  accessed_date: string | null;  // Last access date, if stored
+  comment: string | null;        // Entry comment, if any
+  symlink_target: string | null; // Target of a symbolic link
+}
+
+/**
+ * Archive-level properties returned by openArchive
+ * openArchive 返回的压缩包级别属性
+ */
+export interface ArchiveInfo {
+  archive_type: string;             // Detected format, e.g. "7z" or "zip"
+  physical_size: number | null;     // Archive size on disk in bytes
+  headers_size: number | null;      // Size of the archive headers in bytes
+  method: string | null;            // Compression method(s)
+  solid: boolean;                   // Whether the archive is solid
+  blocks: number | null;            // Number of solid blocks
+  volumes: number | null;           // Number of volumes
+  comment: string | null;           // Archive comment
+  compression_ratio: number | null; // Physical size / unpacked size
+  warnings: string[];               // Warnings printed by 7-Zip
+}
+
+/**
+ * An opened archive: its properties and entries
+ * 已打开的压缩包：其属性和条目
+ */
+export interface OpenedArchive {
+  info: ArchiveInfo;
+  entries: FileItem[];
 }
 
 /**
@@ -62,15 +98,19 @@ export async function selectDestinationFolder(): Promise<string | null> {
  * 
  * @param archivePath - Path to the archive file
  *                    - 压缩包文件路径
- * @returns - List of files and directories in the archive
- *          - 压缩包中的文件和目录列表
+ * @param password - Optional password for archives with encrypted file names
+ *                 - 用于文件名已加密的压缩包的可选密码
+ * @returns - Archive properties and the list of files and directories in the archive
+ *          - 压缩包属性以及压缩包中的文件和目录列表
  */
-export async function openArchive(archivePath: string): Promise<FileItem[]> {
+export async function openArchive(archivePath: string, password?: string): Promise<OpenedArchive> {
   try {
-    return await invoke<FileItem[]>('open_archive', { archivePath });
+    return await invoke<OpenedArchive>('open_archive', { archivePath, password: password ?? null });
   } catch (error) {
     console.error('Failed to open archive:', error);
-    throw new Error(`打开压缩包失败: ${error}`);
+    // Rethrow the structured error so callers can react to its code
+    // 重新抛出结构化错误，以便调用方根据错误码作出反应
+    throw error;
   }
 }
 
@@ -87,19 +127,630 @@ export async function openArchive(archivePath: string): Promise<FileItem[]> {
  *                       - 要解压的文件/文件夹的相对路径（留空表示全部）
  * @param outputDirectory - Destination directory for extracted files
  *                        - 解压文件的目标目录
+ * @param jobId - Optional operation id that can be passed to cancelOperation
+ *              - 可传给 cancelOperation 的可选操作 id
+ * @param password - Optional password for encrypted archives
+ *                 - 用于加密压缩包的可选密码
+ * @param options - Optional extraction options; omitted fields use safe defaults
+ *                - 可选的解压选项；省略的字段使用安全的默认值
+ * @returns - What the extraction did, including entries with unsafe paths
+ *          - 解压的执行结果，包括路径不安全的条目
  */
 export async function extractFiles(
   archivePath: string,
   filesToExtract: string[],
-  outputDirectory: string
-): Promise<void> {
-  await invoke<void>('extract_files', {
+  outputDirectory: string,
+  jobId?: string,
+  password?: string,
+  options?: ExtractOptions
+): Promise<ExtractionReport> {
+  return await invoke<ExtractionReport>('extract_files', {
     archivePath,
     filesToExtract,
     outputDirectory,
+    password: password ?? null,
+    options: options ?? null,
+    jobId: jobId ?? null,
+  });
+}
+
+/**
+ * What to do with entries whose paths would escape the output directory
+ * 如何处理路径会越出输出目录的条目
+ */
+export type UnsafePathPolicy = "refuse" | "skip" | "sanitize";
+
+/**
+ * Why an entry path was considered unsafe
+ * 条目路径被视为不安全的原因
+ */
+export type UnsafePathReason =
+  | "absolute_path"
+  | "drive_letter"
+  | "parent_traversal"
+  | "symlink_escape"
+  | "through_symlink";
+
+/**
+ * What to do with files that already exist in the destination; "ask" extracts nothing and returns the conflicts
+ * 如何处理目标位置已存在的文件；"ask" 不解压任何内容并返回冲突列表
+ */
+export type OverwritePolicy =
+  | "ask"
+  | "overwrite_all"
+  | "skip_existing"
+  | "rename_extracted"
+  | "rename_existing";
+
+/**
+ * "direct" extracts into the output directory as given; "smart" ("extract here") adds a subfolder
+ * named after the archive only when the archive has several top-level entries
+ * "direct" 按原样解压到输出目录；"smart"（"解压到此处"）仅在压缩包有多个顶层条目时添加以压缩包命名的子文件夹
+ */
+export type ExtractLayout = "direct" | "smart";
+
+/**
+ * "full" keeps the stored paths; "relative" strips base_path (usually the browsed folder) from them;
+ * "flatten" puts all selected files into the output directory and renames duplicate names to name_N.ext
+ * "full" 保留存储的路径；"relative" 去掉其中的 base_path（通常是正在浏览的文件夹）；
+ * "flatten" 将所有所选文件放入输出目录，并将重名文件重命名为 name_N.ext
+ */
+export type PathMode = "full" | "relative" | "flatten";
+
+/**
+ * Options for extractFiles
+ * extractFiles 的选项
+ */
+export interface ExtractOptions {
+  unsafe_paths?: UnsafePathPolicy; // Defaults to "refuse" / 默认为 "refuse"
+  overwrite?: OverwritePolicy;     // Defaults to "ask" / 默认为 "ask"
+  layout?: ExtractLayout;          // Defaults to "direct" / 默认为 "direct"
+  path_mode?: PathMode;            // Defaults to "full" / 默认为 "full"
+  base_path?: string;              // Folder inside the archive for "relative", e.g. "docs/images" / "relative" 使用的压缩包内文件夹，例如 "docs/images"
+  limits?: ExtractionLimits;       // Defaults protect against decompression bombs / 默认值可防止解压炸弹
+  disk_space_margin?: number;      // Bytes to keep free after extraction, default 0 / 解压后需保留的空闲字节数，默认为 0
+}
+
+/**
+ * Limits against decompression bombs; null disables a limit, omitted fields keep the default
+ * 防止解压炸弹的限制；null 表示禁用该限制，省略的字段保留默认值
+ */
+export interface ExtractionLimits {
+  max_total_size?: number | null;        // Total unpacked bytes, default 64 GiB
+  max_compression_ratio?: number | null; // Per-entry unpacked/packed ratio, default 1000
+  max_entries?: number | null;           // Entries extracted at once, default 1,000,000
+  max_path_depth?: number | null;        // Folder depth of an entry path, default 64
+  max_nesting_depth?: number | null;     // Archives opened inside one another, default 8
+}
+
+/**
+ * Result of an extraction returned by the backend
+ * 后端返回的解压结果
+ */
+export interface ExtractionReport {
+  output_directory: string; // The directory actually used (may be a new subfolder with "smart")
+  blocked_entries: {
+    path: string;                  // Path inside the archive
+    reason: UnsafePathReason;
+    sanitized_path: string | null; // Where it was extracted instead, null if skipped
+  }[];
+  conflicts: string[]; // With "ask": entries that already exist; if non-empty nothing was written
+}
+
+/**
+ * Structured error returned by the backend archive commands
+ * 后端压缩包命令返回的结构化错误
+ */
+export interface SoarZipError {
+  code: string;    // Stable error code, e.g. "ARCHIVE_NOT_FOUND"
+  message: string; // Human-readable description
+}
+
+/**
+ * Error code returned when an archive is encrypted and the password is missing or wrong
+ * 当压缩包已加密且密码缺失或错误时返回的错误码
+ */
+export const PASSWORD_REQUIRED = "PASSWORD_REQUIRED";
+
+/**
+ * Error code returned by an operation that was cancelled via cancelOperation
+ * 通过 cancelOperation 取消的操作所返回的错误码
+ */
+export const OPERATION_CANCELLED = "OPERATION_CANCELLED";
+
+/**
+ * Error code returned when an extraction was refused because entries would escape the output directory
+ * 当条目会越出输出目录而拒绝解压时返回的错误码
+ */
+export const UNSAFE_ENTRY_PATHS = "UNSAFE_ENTRY_PATHS";
+
+/**
+ * Error code returned when an extraction exceeds a size, ratio, entry count, path depth or archive nesting limit
+ * 当解压超出大小、压缩比、条目数、路径深度或压缩包嵌套层数限制时返回的错误码
+ */
+export const EXTRACTION_LIMIT_EXCEEDED = "EXTRACTION_LIMIT_EXCEEDED";
+
+/**
+ * Error code returned when the destination disk has not enough free space
+ * 当目标磁盘可用空间不足时返回的错误码
+ */
+export const DISK_FULL = "DISK_FULL";
+
+/**
+ * Gets the stable code of a backend error
+ * 获取后端错误的稳定错误码
+ * 
+ * @param error - Value thrown by an invoke call
+ *              - invoke 调用抛出的值
+ * @returns - The error code, or null if the value is not a structured error
+ *          - 错误码，如果该值不是结构化错误则为 null
+ */
+export function getErrorCode(error: unknown): string | null {
+  if (typeof error === "object" && error !== null && "code" in error) {
+    return String((error as SoarZipError).code);
+  }
+  return null;
+}
+
+/**
+ * Gets a displayable message from any thrown value
+ * 从任意抛出的值中获取可显示的消息
+ * 
+ * @param error - Value thrown by an invoke call
+ *              - invoke 调用抛出的值
+ * @returns - The error message
+ *          - 错误消息
+ */
+export function getErrorMessage(error: unknown): string {
+  if (typeof error === "object" && error !== null && "message" in error) {
+    return String((error as { message: unknown }).message);
+  }
+  return String(error);
+}
+
+/**
+ * Cancels a running extraction or compression
+ * 取消正在运行的解压或压缩操作
+ * 
+ * @param jobId - Id of the operation to cancel
+ *              - 要取消的操作 id
+ * @returns - True if the operation was running and has been asked to stop
+ *          - 如果操作正在运行且已被要求停止则为 true
+ */
+export async function cancelOperation(jobId: string): Promise<boolean> {
+  return await invoke<boolean>('cancel_operation', { jobId });
+}
+
+/**
+ * Archive formats supported when creating a new archive
+ * 创建新压缩包时支持的格式
+ */
+export type ArchiveFormat = "zip" | "7z" | "tar" | "tar.gz" | "tar.xz";
+
+/**
+ * Summary of a newly created archive returned by the backend
+ * 后端返回的新建压缩包摘要
+ */
+export interface CreatedArchive {
+  archive_path: string;  // Absolute path of the written archive
+  format: ArchiveFormat; // Format the archive was written in
+  archive_size: number;  // Archive size on disk in bytes
+  encrypted: boolean;    // Whether the archive is password protected
+  warnings: string[];    // Warnings 7-Zip reported, e.g. locked files that were left out
+}
+
+/**
+ * Cipher for encrypted zip archives; "zip_crypto" is weak and must be chosen explicitly
+ * 加密 zip 压缩包使用的算法；"zip_crypto" 强度较弱，必须明确选择
+ */
+export type EncryptionMethod = "aes256" | "zip_crypto";
+
+/**
+ * Password protection for a new 7z or zip archive
+ * 新建 7z 或 zip 压缩包的密码保护选项
+ */
+export interface EncryptionOptions {
+  password: string;
+  encrypt_file_names?: boolean; // 7z only / 仅限 7z
+  method?: EncryptionMethod;    // Defaults to "aes256" / 默认为 "aes256"
+}
+
+/**
+ * Creates a new archive from files and folders on disk
+ * 从磁盘上的文件和文件夹创建新的压缩包
+ * 
+ * @param sourcePaths - Files and folders to compress
+ *                    - 要压缩的文件和文件夹
+ * @param archivePath - Path of the archive to create (must not exist yet)
+ *                    - 要创建的压缩包路径（必须尚不存在）
+ * @param format - Archive format to write
+ *               - 要写入的压缩包格式
+ * @param relativeRoot - Optional directory that entry paths are relative to
+ *                     - 可选的目录，条目路径将相对于该目录
+ * @param jobId - Optional operation id that can be passed to cancelOperation
+ *              - 可传给 cancelOperation 的可选操作 id
+ * @param encryption - Optional password protection (7z and zip only)
+ *                   - 可选的密码保护（仅限 7z 和 zip）
+ * @returns - Summary of the created archive
+ *          - 已创建压缩包的摘要
+ */
+export async function createArchive(
+  sourcePaths: string[],
+  archivePath: string,
+  format: ArchiveFormat,
+  relativeRoot?: string,
+  jobId?: string,
+  encryption?: EncryptionOptions
+): Promise<CreatedArchive> {
+  return await invoke<CreatedArchive>('create_archive', {
+    sourcePaths,
+    archivePath,
+    format,
+    relativeRoot: relativeRoot ?? null,
+    encryption: encryption ?? null,
+    jobId: jobId ?? null,
+  });
+}
+
+/**
+ * How to handle files that already exist inside the archive when adding
+ * 添加文件时如何处理压缩包内已存在的文件
+ */
+export type AddConflictPolicy = "replace" | "skip" | "keep_both";
+
+/**
+ * Adds files and folders from disk to an existing archive
+ * 将磁盘上的文件和文件夹添加到现有压缩包
+ * 
+ * @param archivePath - Path to the archive file
+ *                    - 压缩包文件路径
+ * @param targetDir - Folder inside the archive to add to ("" for the root)
+ *                  - 压缩包内要添加到的文件夹（"" 表示根目录）
+ * @param sourcePaths - Files and folders on disk to add
+ *                    - 要添加的磁盘文件和文件夹
+ * @param conflictPolicy - How to resolve conflicts with existing entries
+ *                       - 如何解决与现有条目的冲突
+ * @returns - The refreshed list of files and directories in the archive
+ *          - 刷新后的压缩包文件和目录列表
+ */
+export async function addToArchive(
+  archivePath: string,
+  targetDir: string,
+  sourcePaths: string[],
+  conflictPolicy: AddConflictPolicy
+): Promise<FileItem[]> {
+  return await invoke<FileItem[]>('add_to_archive', {
+    archivePath,
+    targetDir,
+    sourcePaths,
+    conflictPolicy,
+  });
+}
+
+/**
+ * Deletes files and folders from an archive (folders are deleted recursively)
+ * 从压缩包中删除文件和文件夹（文件夹会被递归删除）
+ * 
+ * @param archivePath - Path to the archive file
+ *                    - 压缩包文件路径
+ * @param files - Paths inside the archive to delete
+ *              - 要删除的压缩包内路径
+ * @returns - The refreshed list of files and directories in the archive
+ *          - 刷新后的压缩包文件和目录列表
+ */
+export async function deleteFilesInArchive(archivePath: string, files: string[]): Promise<FileItem[]> {
+  return await invoke<FileItem[]>('delete_files_in_archive', { archivePath, files });
+}
+
+/**
+ * Renames or moves a file or folder inside an archive
+ * 重命名或移动压缩包内的文件或文件夹
+ * 
+ * @param archivePath - Path to the archive file
+ *                    - 压缩包文件路径
+ * @param oldPath - Current path of the entry inside the archive
+ *                - 条目在压缩包内的当前路径
+ * @param newPath - New path of the entry inside the archive (must not exist yet)
+ *                - 条目在压缩包内的新路径（必须尚不存在）
+ * @returns - The refreshed list of files and directories in the archive
+ *          - 刷新后的压缩包文件和目录列表
+ */
+export async function renameInArchive(archivePath: string, oldPath: string, newPath: string): Promise<FileItem[]> {
+  return await invoke<FileItem[]>('rename_in_archive', { archivePath, oldPath, newPath });
+}
+
+/**
+ * Reason an entry failed the integrity test
+ * 条目未通过完整性测试的原因
+ */
+export type TestErrorKind =
+  | "crc_failed"
+  | "data_error"
+  | "unexpected_end"
+  | "wrong_password"
+  | "unsupported_method"
+  | "other";
+
+/**
+ * Integrity test report returned by testArchive
+ * testArchive 返回的完整性测试报告
+ */
+export interface ArchiveTestReport {
+  archive_path: string;
+  total_entries: number;   // File entries in the archive
+  passed_entries: number;  // File entries that passed
+  failed_entries: { path: string; error: TestErrorKind; message: string }[];
+  archive_errors: string[]; // Errors concerning the whole archive
+  verdict: "ok" | "damaged" | "unreadable";
+}
+
+/**
+ * Tests the integrity of an archive
+ * 测试压缩包的完整性
+ * 
+ * @param archivePath - Path to the archive file
+ *                    - 压缩包文件路径
+ * @param password - Optional password for encrypted archives
+ *                 - 用于加密压缩包的可选密码
+ * @param jobId - Optional operation id that can be passed to cancelOperation
+ *              - 可传给 cancelOperation 的可选操作 id
+ * @returns - The test report
+ *          - 测试报告
+ */
+export async function testArchive(
+  archivePath: string,
+  password?: string,
+  jobId?: string
+): Promise<ArchiveTestReport> {
+  return await invoke<ArchiveTestReport>('test_archive', {
+    archivePath,
+    password: password ?? null,
+    jobId: jobId ?? null,
+  });
+}
+
+/**
+ * Preview of a single entry returned by readEntry
+ * readEntry 返回的单个条目的预览
+ */
+export type EntryPreview =
+  | { kind: "text"; text: string; encoding: string; truncated: boolean }     // truncated: only the beginning was read / 只读取了开头部分
+  | { kind: "image"; mime_type: string; data: string }                       // data is base64 / data 为 base64
+  | { kind: "binary"; hex_dump: string; mime_type: string | null; truncated: boolean };
+
+/**
+ * Reads a single entry for a preview without extracting it
+ * 在不解压的情况下读取单个条目以供预览
+ * 
+ * @param archivePath - Path to the archive file
+ *                    - 压缩包文件路径
+ * @param entryPath - Path of the entry inside the archive
+ *                  - 条目在压缩包内的路径
+ * @param password - Optional password for encrypted archives
+ *                 - 用于加密压缩包的可选密码
+ * @param maxBytes - Optional cap on the bytes read, 4 MiB by default
+ *                 - 读取字节数的可选上限，默认为 4 MiB
+ * @returns - Decoded text, an image, or a hex dump
+ *          - 已解码的文本、图片或十六进制转储
+ */
+export async function readEntry(
+  archivePath: string,
+  entryPath: string,
+  password?: string,
+  maxBytes?: number
+): Promise<EntryPreview> {
+  return await invoke<EntryPreview>('read_entry', {
+    archivePath,
+    entryPath,
+    password: password ?? null,
+    maxBytes: maxBytes ?? null,
+  });
+}
+
+/**
+ * Opens an entry with its default application
+ * 用默认应用程序打开条目
+ * 
+ * The entry is extracted to a temporary file that is deleted when the archive is closed or the app exits
+ * 条目会被解压到临时文件，该文件会在压缩包关闭或应用退出时被删除
+ * 
+ * @param archivePath - Path to the archive file
+ *                    - 压缩包文件路径
+ * @param entryPath - Path of the entry inside the archive
+ *                  - 条目在压缩包内的路径
+ * @param password - Optional password for encrypted archives
+ *                 - 用于加密压缩包的可选密码
+ * @returns - Path of the temporary file that was opened
+ *          - 被打开的临时文件的路径
+ */
+export async function openEntry(archivePath: string, entryPath: string, password?: string): Promise<string> {
+  return await invoke<string>('open_entry', {
+    archivePath,
+    entryPath,
+    password: password ?? null,
+  });
+}
+
+/**
+ * Writes an opened entry back into the archive after it was edited in another application
+ * 在其他应用程序中编辑后，将已打开的条目写回压缩包
+ * 
+ * @param archivePath - Path to the archive file
+ *                    - 压缩包文件路径
+ * @param entryPath - Path of the entry inside the archive, as passed to openEntry
+ *                  - 条目在压缩包内的路径，与传给 openEntry 的相同
+ * @param password - Optional password for encrypted archives
+ *                 - 用于加密压缩包的可选密码
+ * @returns - Updated list of files in the archive
+ *          - 压缩包更新后的文件列表
+ */
+export async function updateEntry(archivePath: string, entryPath: string, password?: string): Promise<FileItem[]> {
+  return await invoke<FileItem[]>('update_entry', {
+    archivePath,
+    entryPath,
+    password: password ?? null,
   });
 }
 
+/**
+ * An opened entry whose temporary file was changed by another application
+ * 临时文件被其他应用程序修改过的已打开条目
+ */
+export interface ModifiedEntry {
+  archive_path: string;  // Archive the entry belongs to
+  entry_path: string;    // Path of the entry inside the archive
+  path: string;          // The temporary file
+}
+
+/**
+ * Subscribes to notifications about opened entries that were saved in another application
+ * 订阅已打开条目在其他应用程序中被保存的通知
+ * 
+ * @param onModified - Callback invoked once for every saved change
+ *                   - 每次保存的更改调用一次的回调
+ * @returns - Function that removes the listener
+ *          - 移除监听器的函数
+ */
+export async function listenToEntryModified(
+  onModified: (entry: ModifiedEntry) => void
+): Promise<UnlistenFn> {
+  return await listen<ModifiedEntry>('entry-modified', (event) => onModified(event.payload));
+}
+
+/**
+ * Deletes the temporary files of entries opened from an archive that is being closed
+ * 删除从正在关闭的压缩包中打开的条目的临时文件
+ * 
+ * @param archivePath - Path of the archive being closed
+ *                    - 正在关闭的压缩包的路径
+ * @returns - Number of temporary files removed
+ *          - 被删除的临时文件数
+ */
+export async function closeArchive(archivePath: string): Promise<number> {
+  return await invoke<number>('close_archive', { archivePath });
+}
+
+/**
+ * Progress notification emitted by the backend during long archive operations
+ * 后端在长时间压缩包操作期间发出的进度通知
+ */
+export interface ProgressUpdate {
+  job_id: string;               // Operation id, usable with cancelOperation
+  operation: string;            // Operation kind, e.g. "extract" or "compress"
+  percent: number;              // Overall completion from 0 to 100
+  current_file: string | null;  // Entry currently being processed
+  bytes_processed: number;      // Estimated bytes processed so far
+  total_bytes: number;          // Total bytes expected
+  eta_seconds: number | null;   // Estimated remaining time in seconds
+}
+
+/**
+ * Subscribes to progress events of running archive operations
+ * 订阅正在运行的压缩包操作的进度事件
+ * 
+ * @param onProgress - Callback invoked for every progress update
+ *                   - 每次进度更新时调用的回调
+ * @returns - Function that removes the listener
+ *          - 移除监听器的函数
+ */
+export async function listenToArchiveProgress(
+  onProgress: (update: ProgressUpdate) => void
+): Promise<UnlistenFn> {
+  return await listen<ProgressUpdate>('archive-progress', (event) => onProgress(event.payload));
+}
+
+/**
+ * Options of a content search; omitted fields use the backend defaults
+ * 内容搜索的选项；省略的字段使用后端默认值
+ */
+export interface SearchOptions {
+  regex?: boolean;           // Treat the pattern as a regular expression (default: substring)
+  case_sensitive?: boolean;  // Match case exactly (default: false)
+  max_file_size?: number;    // Skip entries larger than this many bytes (default: 16 MiB)
+  context_lines?: number;    // Lines sent before and after each match (default: 2)
+  max_matches?: number;      // Stop after this many matching lines (default: 1000)
+  paths?: string[];          // Entries or folders to search (default: whole archive)
+}
+
+/**
+ * A matching line emitted by the backend while a content search runs
+ * 内容搜索运行期间后端发出的匹配行
+ */
+export interface SearchMatch {
+  job_id: string;            // Id of the search, usable with cancelOperation
+  entry_path: string;        // Path of the entry inside the archive
+  line_number: number;       // Line number, starting at 1
+  column: number;            // Column of the first match, starting at 1
+  line: string;              // The matching line (shortened around the match if very long)
+  context_before: string[];  // Lines before the match
+  context_after: string[];   // Lines after the match
+}
+
+/**
+ * Summary of a finished content search
+ * 已完成的内容搜索的摘要
+ */
+export interface SearchReport {
+  entries_searched: number;
+  entries_matched: number;
+  match_count: number;
+  skipped_too_large: string[];  // Entries above max_file_size
+  skipped_binary: string[];     // Entries that are not text
+  failed: string[];             // Entries that could not be read
+  limit_reached: boolean;       // Whether the search stopped at max_matches
+}
+
+/**
+ * Searches the content of the entries of an archive
+ * 搜索压缩包条目的内容
+ * 
+ * Matches arrive through listenToSearchMatches while the search runs
+ * 匹配结果会在搜索运行期间通过 listenToSearchMatches 送达
+ * 
+ * @param archivePath - Path to the archive file
+ *                    - 压缩包文件路径
+ * @param pattern - Text or regular expression to find
+ *                - 要查找的文本或正则表达式
+ * @param options - Optional search options
+ *                - 可选的搜索选项
+ * @param jobId - Optional operation id that can be passed to cancelOperation
+ *              - 可传给 cancelOperation 的可选操作 id
+ * @param password - Optional password for encrypted archives
+ *                 - 用于加密压缩包的可选密码
+ * @returns - Summary of the search
+ *          - 搜索摘要
+ */
+export async function searchArchiveContents(
+  archivePath: string,
+  pattern: string,
+  options?: SearchOptions,
+  jobId?: string,
+  password?: string
+): Promise<SearchReport> {
+  return await invoke<SearchReport>('search_archive_contents', {
+    archivePath,
+    pattern,
+    password: password ?? null,
+    options: options ?? null,
+    jobId: jobId ?? null,
+  });
+}
+
+/**
+ * Subscribes to the matches of running content searches
+ * 订阅正在运行的内容搜索的匹配结果
+ * 
+ * @param onMatch - Callback invoked for every matching line
+ *                - 每个匹配行调用的回调
+ * @returns - Function that removes the listener
+ *          - 移除监听器的函数
+ */
+export async function listenToSearchMatches(
+  onMatch: (match: SearchMatch) => void
+): Promise<UnlistenFn> {
+  return await listen<SearchMatch>('archive-search-match', (event) => onMatch(event.payload));
+}
+
 /**
  * Filters files to show only those in the specified folder
  * 过滤指定文件夹下的文件
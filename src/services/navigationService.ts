@@ -2,6 +2,8 @@
  * Navigation Service Module - Handles navigation history and path management
  * 导航服务模块 - 处理导航历史和路径管理
  */
+import { getCurrentArchivePath } from "./appState";
+import { isNestedArchivePath } from "./archiveService";
 
 /**
  * Class that manages navigation history for browsing archives
@@ -202,7 +204,7 @@ export function updateNavButtonsState(): void {
   // Update up button state
   if (upBtn) {
     const currentPath = navigationHistory.getCurrentPath();
-    if (currentPath) {
+    if (currentPath || isNestedArchivePath(getCurrentArchivePath())) {
       upBtn.classList.remove('disabled');
     } else {
       upBtn.classList.add('disabled');
@@ -108,18 +108,14 @@ export function getFileNameWithoutExtension(filePath: string): string {
 }
 
 /**
- * 生成默认解压路径 (同目录下同名文件夹)
+ * 生成默认解压路径 (压缩包所在目录)
+ * 是否创建同名文件夹由后端的智能解压决定，以避免出现 foo/foo 这样的多余嵌套
  * @param archivePath 压缩包路径
  * @returns 默认解压路径
  */
 export function getDefaultExtractPath(archivePath: string): string {
-  const dirPath = getDirectoryPath(archivePath);
-  const baseName = getFileNameWithoutExtension(archivePath);
   // 在 Windows 和 macOS/Linux 上，路径分隔符可能不同
   // 这里我们统一使用 /，Tauri 和 Rust 通常能更好地处理它
-  // 如果需要特定于操作系统的分隔符，可以使用 tauri/api/path 中的 join
-  if (!dirPath) {
-    return baseName; // 如果没有目录路径，直接返回基础名称
-  }
-  return `${dirPath}/${baseName}`;
-} 
\ No newline at end of file
+  const dirPath = getDirectoryPath(archivePath);
+  return dirPath || '.'; // 如果没有目录路径，则解压到当前目录
+}
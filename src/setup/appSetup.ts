@@ -8,8 +8,10 @@ import { setupLogoClick } from './logo';
 import { setupSettingsButton } from './settings';
 
 // Import necessary functions/services used by setup functions
-import { openArchiveDialogAndLoad, loadArchive } from '../services/archiveService'; // Assuming archiveService exports these
+import { openArchiveDialogAndLoad, loadArchive, openParentArchive } from '../services/archiveService'; // Assuming archiveService exports these
 import { startExtractionProcess } from '../services/extractionService'; // Assuming extractionService exports this
+import { setupEntryUpdates } from '../services/entryService';
+import { openContentSearch } from '../services/contentSearchService';
 import { 
     performSearch, 
     refreshUI, 
@@ -37,7 +39,7 @@ export function setupApplicationEventListeners() {
   // Pass necessary functions and state getters to the setup modules
   setupWindowControls();
   setupMenuItems({ openArchiveDialog: openArchiveDialogAndLoad });
-  setupSearch({ performSearch }); // Pass the uiManager search function
+  setupSearch({ performSearch, openContentSearch }); // Pass the uiManager search function and the content search
   setupNavButtons({
       isLoading: getIsLoading,
       canGoBack: () => navigationHistory.canGoBack(),
@@ -50,7 +52,8 @@ export function setupApplicationEventListeners() {
       navigateToFolder: navigateToFolder, // Pass the uiManager navigation function
       getArchivePath: getCurrentArchivePath,
       updateLoadingStatus: updateLoadingStatus, // Pass the imported function
-      setCurrentFiles: setCurrentFiles
+      setCurrentFiles: setCurrentFiles,
+      openParentArchive: openParentArchive
   });
   setupToolbarButtons({ 
       getArchivePath: getCurrentArchivePath, 
@@ -63,6 +66,7 @@ export function setupApplicationEventListeners() {
       resetApp: resetAppToHome // Use the uiManager reset function
   });
   setupSettingsButton({});
+  void setupEntryUpdates(); // Offer to write back files edited in other applications
 
   console.log("Application event listeners and component interactions set up.");
 }
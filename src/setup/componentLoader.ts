@@ -5,6 +5,9 @@ import fileExplorerHtml from '../ui/components/file-explorer.html?raw';
 import statusBarHtml from '../ui/components/status-bar.html?raw';
 import extractDialogHtml from '../ui/components/extract-dialog.html?raw';
 import confirmDialogHtml from '../ui/components/confirm-dialog.html?raw';
+import conflictDialogHtml from '../ui/components/conflict-dialog.html?raw';
+import previewDialogHtml from '../ui/components/preview-dialog.html?raw';
+import searchDialogHtml from '../ui/components/search-dialog.html?raw';
 import { showError } from '../ui/notification';
 
 /**
@@ -48,13 +51,15 @@ export function loadAllComponents() {
   loadComponent(statusBarHtml, 'status-bar-placeholder', 'status-bar.html');
   loadComponent(extractDialogHtml, 'dialog-placeholder', 'extract-dialog.html');
 
-  // Append the confirm dialog HTML to the body
-  const confirmDialogContainer = document.createElement('div');
-  confirmDialogContainer.innerHTML = confirmDialogHtml;
-  // Append each top-level element from the confirm dialog HTML to the body
-  // This prevents adding an extra wrapper div if confirmDialogHtml has a single root
-  while (confirmDialogContainer.firstChild) {
-    document.body.appendChild(confirmDialogContainer.firstChild);
+  // Append the confirm, conflict, preview and search dialog HTML to the body
+  for (const dialogHtml of [confirmDialogHtml, conflictDialogHtml, previewDialogHtml, searchDialogHtml]) {
+    const dialogContainer = document.createElement('div');
+    dialogContainer.innerHTML = dialogHtml;
+    // Append each top-level element from the dialog HTML to the body
+    // This prevents adding an extra wrapper div if the dialog HTML has a single root
+    while (dialogContainer.firstChild) {
+      document.body.appendChild(dialogContainer.firstChild);
+    }
   }
 
   console.log("All UI components inserted.");
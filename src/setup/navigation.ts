@@ -2,7 +2,7 @@
  * Navigation Setup Module - Configures navigation controls and buttons
  * 导航设置模块 - 配置导航控件和按钮
  */
-import { openArchive } from "../services/fileService";
+import { openArchive, getErrorMessage } from "../services/fileService";
 import { showError } from "../ui/notification";
 
 /**
@@ -23,6 +23,8 @@ export interface NavigationDependencies {
   getArchivePath: () => string;
   updateLoadingStatus: (loading: boolean, message?: string) => void;
   setCurrentFiles: (files: any[]) => void; // Assuming FileItem type, adjust if needed
+  // Leaves a nested archive for the archive containing it
+  openParentArchive: () => Promise<boolean>;
 }
 
 /**
@@ -68,6 +70,9 @@ export function setupNavButtons(deps: NavigationDependencies): void {
     if (currentPath) {
       const parentPath = deps.getParentPath(currentPath);
       deps.navigateToFolder(parentPath);
+    } else {
+      // At the root of a nested archive, go up to the archive containing it
+      deps.openParentArchive();
     }
   });
   
@@ -80,7 +85,7 @@ export function setupNavButtons(deps: NavigationDependencies): void {
       deps.updateLoadingStatus(true, "正在刷新...");
       
       // Reload the current archive
-      const files = await openArchive(archivePath);
+      const { entries: files } = await openArchive(archivePath);
       deps.setCurrentFiles(files); // Update files in main.ts state
       
       // Refresh UI
@@ -90,7 +95,7 @@ export function setupNavButtons(deps: NavigationDependencies): void {
       // showSuccess("刷新完成"); 
     } catch (error) {
       console.error('刷新失败:', error);
-      showError(`刷新失败: ${error}`);
+      showError(`刷新失败: ${getErrorMessage(error)}`);
     } finally {
       deps.updateLoadingStatus(false);
     }
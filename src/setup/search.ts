@@ -9,6 +9,7 @@
  */
 export interface SearchDependencies {
   performSearch: (query: string) => void;
+  openContentSearch: (query: string) => void;
 }
 
 /**
@@ -21,6 +22,7 @@ export interface SearchDependencies {
 export function setupSearch(deps: SearchDependencies): void {
   const searchInput = document.querySelector('.search-input');
   const searchBtn = document.querySelector('.search-btn');
+  const contentSearchBtn = document.querySelector('.content-search-btn');
   
   // Search button click
   searchBtn?.addEventListener('click', () => {
@@ -30,6 +32,11 @@ export function setupSearch(deps: SearchDependencies): void {
     }
   });
   
+  // Content search button click, prefilled with the name search text
+  contentSearchBtn?.addEventListener('click', () => {
+    deps.openContentSearch((searchInput as HTMLInputElement)?.value ?? '');
+  });
+  
   // Trigger search on Enter key press
   searchInput?.addEventListener('keypress', (e: Event) => {
     // Check if the event is a KeyboardEvent before accessing 'key'
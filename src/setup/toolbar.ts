@@ -2,8 +2,12 @@
  * Toolbar Setup Module - Configures the application toolbar buttons
  * 工具栏设置模块 - 配置应用程序工具栏按钮
  */
-import { showError, showInfo } from '../ui/notification';
+import { deleteFilesInArchive, getErrorMessage } from '../services/fileService';
+import { setCurrentFiles, setIsLoading } from '../services/appState';
+import { showError, showInfo, showSuccess } from '../ui/notification';
 import { getSelectedFiles } from '../ui/fileExplorer';
+import { showConfirmDialog } from '../ui/confirmDialog';
+import { refreshUI } from '../ui/uiManager';
 
 /**
  * Interface for dependencies needed by toolbar setup
@@ -95,25 +99,38 @@ export function setupToolbarButtons(deps: ToolbarDependencies): void {
 }
 
 /**
- * Placeholder handler for the delete action.
- * 删除操作的占位符处理程序。
+ * Handles the delete action: asks for confirmation, deletes the selected entries
+ * from the archive and refreshes the file list.
+ * 处理删除操作：请求确认，从压缩包中删除选中的条目并刷新文件列表。
  * 
- * @param _deps - Toolbar dependencies.
- *              - 工具栏依赖项。
+ * @param deps - Toolbar dependencies.
+ *             - 工具栏依赖项。
  */
-function handleDelete(_deps: ToolbarDependencies): void {
+function handleDelete(deps: ToolbarDependencies): void {
   const filesToDelete = getSelectedFiles();
   if (filesToDelete.length === 0) {
     showInfo("请先选择要删除的文件或文件夹。");
     return;
   }
 
-  console.log("Attempting to delete files (not implemented):", filesToDelete);
-  // TODO: Implement actual deletion logic by calling a backend function.
-  // Example: await invoke('delete_files_in_archive', { archivePath: deps.getArchivePath(), files: filesToDelete });
-  // Then refresh the UI: refreshUI(); (need to import refreshUI from main)
-
-  showInfo(`删除功能正在开发中。选中的 ${filesToDelete.length} 个项目未被删除。`);
+  showConfirmDialog(
+    `确定要从压缩包中删除选中的 ${filesToDelete.length} 个项目吗？此操作无法撤销。`,
+    async () => {
+      const archivePath = deps.getArchivePath();
+      try {
+        setIsLoading(true);
+        const files = await deleteFilesInArchive(archivePath, filesToDelete);
+        setCurrentFiles(files);
+        refreshUI();
+        showSuccess(`已删除 ${filesToDelete.length} 个项目`);
+      } catch (error) {
+        console.error("Failed to delete files in archive:", error);
+        showError(`删除失败: ${getErrorMessage(error)}`);
+      } finally {
+        setIsLoading(false);
+      }
+    }
+  );
 }
 
 /**
@@ -0,0 +1,110 @@
+import type { OverwritePolicy } from "../services/fileService";
+
+// DOM Element References
+let overlay: HTMLElement | null = null;
+let messageElement: HTMLElement | null = null;
+let listElement: HTMLElement | null = null;
+
+// Callback for the chosen policy, or null when the dialog was cancelled
+let onResolveCallback: ((policy: OverwritePolicy | null) => void) | null = null;
+
+// State
+let isInitialized = false;
+let isVisible = false;
+let isHiding = false;
+
+/**
+ * Maximum number of conflicting paths listed in the dialog
+ * 对话框中列出的冲突路径的最大数量
+ */
+const MAX_LISTED_CONFLICTS = 10;
+
+function initializeDialog() {
+    if (isInitialized) return;
+
+    overlay = document.getElementById('conflict-dialog-overlay');
+    messageElement = document.getElementById('conflict-dialog-message');
+    listElement = document.getElementById('conflict-dialog-list');
+    const buttons: [string, OverwritePolicy | null][] = [
+        ['conflict-overwrite-btn', 'overwrite_all'],
+        ['conflict-skip-btn', 'skip_existing'],
+        ['conflict-rename-btn', 'rename_extracted'],
+        ['conflict-cancel-btn', null],
+    ];
+
+    if (!overlay || !messageElement || !listElement || buttons.some(([id]) => !document.getElementById(id))) {
+        console.error('Conflict dialog elements not found in DOM!');
+        return;
+    }
+
+    for (const [id, policy] of buttons) {
+        document.getElementById(id)!.addEventListener('click', () => resolve(policy));
+    }
+    overlay.addEventListener('click', (event) => {
+        // Close only if clicking directly on the overlay, not the dialog content
+        if (event.target === overlay) {
+            resolve(null);
+        }
+    });
+    overlay.addEventListener('transitionend', handleTransitionEnd);
+
+    isInitialized = true;
+}
+
+function resolve(policy: OverwritePolicy | null) {
+    if (!isVisible || isHiding || !overlay) return;
+    const callback = onResolveCallback;
+    onResolveCallback = null;
+    isHiding = true;
+    overlay.classList.remove('visible');
+    // Actual hiding (display: none) is handled in handleTransitionEnd
+    callback?.(policy);
+}
+
+function handleTransitionEnd(event: TransitionEvent) {
+    if (overlay && event.target === overlay && event.propertyName === 'opacity' && !overlay.classList.contains('visible')) {
+        overlay.style.display = 'none';
+        isHiding = false;
+        isVisible = false;
+    }
+}
+
+/**
+ * Shows the extraction conflict dialog.
+ * 显示解压冲突对话框。
+ *
+ * @param conflicts - Paths inside the archive whose files already exist in the destination.
+ *                  - 在目标位置已存在对应文件的压缩包内路径。
+ * @returns - The policy the user chose, or null if the extraction was cancelled.
+ *          - 用户选择的策略；如果解压被取消则为 null。
+ */
+export function showConflictDialog(conflicts: string[]): Promise<OverwritePolicy | null> {
+    initializeDialog();
+    if (!isInitialized || !overlay || !messageElement || !listElement || isVisible || isHiding) {
+        console.warn("Conflict dialog cannot be shown.");
+        return Promise.resolve(null);
+    }
+
+    messageElement.textContent = `目标文件夹中已存在 ${conflicts.length} 个同名文件，请选择处理方式：`;
+    listElement.replaceChildren(
+        ...conflicts.slice(0, MAX_LISTED_CONFLICTS).map((path) => {
+            const item = document.createElement('li');
+            item.textContent = path;
+            return item;
+        })
+    );
+    if (conflicts.length > MAX_LISTED_CONFLICTS) {
+        const more = document.createElement('li');
+        more.textContent = `……以及另外 ${conflicts.length - MAX_LISTED_CONFLICTS} 个`;
+        listElement.appendChild(more);
+    }
+
+    return new Promise((resolvePromise) => {
+        onResolveCallback = resolvePromise;
+        overlay!.style.display = 'flex';
+        requestAnimationFrame(() => {
+            overlay!.classList.add('visible');
+            isVisible = true;
+        });
+    });
+}
@@ -1,11 +1,14 @@
 // src/ui/extractDialog.ts
 
+import type { PathMode } from "../services/fileService";
+
 // DOM Element References (initialize later)
 let overlay: HTMLElement | null = null;
 let pathInput: HTMLInputElement | null = null;
 let changePathBtn: HTMLElement | null = null;
 let confirmBtn: HTMLElement | null = null;
 let cancelBtn: HTMLElement | null = null;
+let pathModeSelect: HTMLSelectElement | null = null;
 
 // Function to initialize DOM elements
 function initializeDialogElements() {
@@ -14,6 +17,7 @@ function initializeDialogElements() {
   changePathBtn = document.getElementById('change-path-btn')!;
   confirmBtn = document.getElementById('confirm-extract-btn')!;
   cancelBtn = document.getElementById('cancel-extract-btn')!;
+  pathModeSelect = document.getElementById('extract-path-mode') as HTMLSelectElement;
 }
 
 // Type definitions for callbacks
@@ -26,11 +30,11 @@ function initializeDialogElements() {
 type OnChangePath = () => Promise<string | null | undefined>; 
 /**
  * Type definition for the 'Confirm' callback.
- * Called with the final confirmed extraction path.
+ * Called with the final confirmed extraction path and how the stored paths are kept.
  * "确认"回调的类型定义。
- * 使用最终确认的解压路径调用。
+ * 使用最终确认的解压路径以及存储路径的保留方式调用。
  */
-type OnConfirm = (confirmedPath: string) => void; 
+type OnConfirm = (confirmedPath: string, pathMode: PathMode) => void; 
 /**
  * Type definition for the 'Cancel' callback.
  * Called when the dialog is cancelled or closed.
@@ -90,7 +94,7 @@ export function showExtractDialog(
   }
 
   // Check if elements were found (add null checks)
-  if (!overlay || !pathInput || !changePathBtn || !confirmBtn || !cancelBtn) {
+  if (!overlay || !pathInput || !changePathBtn || !confirmBtn || !cancelBtn || !pathModeSelect) {
     console.error("Extract dialog elements not found!");
     return; // Exit if elements are missing
   }
@@ -98,6 +102,7 @@ export function showExtractDialog(
   currentPath = defaultPath; // Set initial path
   pathInput.value = currentPath; // Display path in the input-like element
   pathInput.title = currentPath; // Set tooltip to show full path on hover
+  pathModeSelect.value = 'full'; // Every extraction starts with full paths / 每次解压都从完整路径开始
 
   // Store the provided callback functions
   onChangePathCallback = onChangePath;
@@ -208,7 +213,8 @@ async function handleChangePathClick() {
  */
 function handleConfirmClick() {
   if (onConfirmCallback) {
-    onConfirmCallback(currentPath); // Pass the final selected path to the callback
+    const pathMode = (pathModeSelect?.value ?? 'full') as PathMode;
+    onConfirmCallback(currentPath, pathMode); // Pass the final selected path and path mode to the callback
   }
   hideDialog(); // Hide the dialog after confirmation
 }
@@ -7,6 +7,7 @@ import { FileItem, getDisplayName, getFileIcon } from "../services/fileService";
 import { showInfo, showError } from "./notification";
 // Import services needed for context menu actions
 import { startExtractionProcess } from "../services/extractionService";
+import { openEntryWithDefaultApp, previewEntry } from "../services/entryService";
 // Import navigation service
 import { navigateToFolder } from "../ui/uiManager";
 // Assuming navigationService exists and handles folder navigation
@@ -139,6 +140,8 @@ function showFileContextMenu(event: MouseEvent, _file: FileItem): void { // _fil
     } else {
       // Single File / 单个文件
       menuList.innerHTML = `
+        <li data-action="open-file">打开</li>
+        <li data-action="preview">预览</li>
         <li data-action="extract">提取到...</li>
         <hr>
         <li data-action="copy-path">复制路径</li>
@@ -175,6 +178,14 @@ function showFileContextMenu(event: MouseEvent, _file: FileItem): void { // _fil
                 console.warn("'打开文件夹'操作在非文件夹或多选状态下被调用");
               }
               break;
+            case 'open-file':
+              // Extract to a temporary file and open it with the default application
+              // 解压到临时文件并用默认应用程序打开
+              await openEntryWithDefaultApp(selectedItems[0]);
+              break;
+            case 'preview':
+              await previewEntry(selectedItems[0]);
+              break;
             case 'extract':
               // Trigger the extraction process using extractionService, passing selected paths
               // 使用 extractionService 触发提取过程，传递选定的路径
@@ -0,0 +1,108 @@
+import type { EntryPreview } from "../services/fileService";
+
+// DOM Element References
+let overlay: HTMLElement | null = null;
+let titleElement: HTMLElement | null = null;
+let infoElement: HTMLElement | null = null;
+let contentElement: HTMLElement | null = null;
+
+// State
+let isInitialized = false;
+let isVisible = false;
+let isHiding = false;
+
+function initializeDialog() {
+    if (isInitialized) return;
+
+    overlay = document.getElementById('preview-dialog-overlay');
+    titleElement = document.getElementById('preview-dialog-title');
+    infoElement = document.getElementById('preview-dialog-info');
+    contentElement = document.getElementById('preview-dialog-content');
+    const closeButton = document.getElementById('preview-close-btn');
+
+    if (!overlay || !titleElement || !infoElement || !contentElement || !closeButton) {
+        console.error('Preview dialog elements not found in DOM!');
+        return;
+    }
+
+    closeButton.addEventListener('click', hideDialog);
+    overlay.addEventListener('click', (event) => {
+        // Close only if clicking directly on the overlay, not the dialog content
+        if (event.target === overlay) {
+            hideDialog();
+        }
+    });
+    overlay.addEventListener('transitionend', handleTransitionEnd);
+
+    isInitialized = true;
+}
+
+function hideDialog() {
+    if (!isVisible || isHiding || !overlay) return;
+    isHiding = true;
+    overlay.classList.remove('visible');
+    // Actual hiding (display: none) is handled in handleTransitionEnd
+}
+
+function handleTransitionEnd(event: TransitionEvent) {
+    if (overlay && event.target === overlay && event.propertyName === 'opacity' && !overlay.classList.contains('visible')) {
+        overlay.style.display = 'none';
+        // Drop the content so a large text or image does not stay in memory
+        // 清除内容，以免大段文本或图片一直占用内存
+        contentElement?.replaceChildren();
+        isHiding = false;
+        isVisible = false;
+    }
+}
+
+/**
+ * Shows the preview of a file inside the archive.
+ * 显示压缩包内文件的预览。
+ *
+ * @param entryName - Path of the entry inside the archive, used as the title.
+ *                  - 条目在压缩包内的路径，用作标题。
+ * @param preview - The preview returned by readEntry.
+ *                - readEntry 返回的预览。
+ */
+export function showPreviewDialog(entryName: string, preview: EntryPreview) {
+    initializeDialog();
+    if (!isInitialized || !overlay || !titleElement || !infoElement || !contentElement || isHiding) {
+        console.warn("Preview dialog cannot be shown.");
+        return;
+    }
+
+    titleElement.textContent = entryName.split('/').filter(Boolean).pop() ?? entryName;
+    titleElement.title = entryName;
+
+    switch (preview.kind) {
+        case 'text': {
+            const pre = document.createElement('pre');
+            pre.textContent = preview.text;
+            contentElement.replaceChildren(pre);
+            infoElement.textContent = `文本 · ${preview.encoding}${preview.truncated ? ' · 文件较大，仅显示开头部分' : ''}`;
+            break;
+        }
+        case 'image': {
+            const image = document.createElement('img');
+            image.alt = entryName;
+            image.src = `data:${preview.mime_type};base64,${preview.data}`;
+            contentElement.replaceChildren(image);
+            infoElement.textContent = `图片 · ${preview.mime_type}`;
+            break;
+        }
+        case 'binary': {
+            const pre = document.createElement('pre');
+            pre.textContent = preview.hex_dump;
+            contentElement.replaceChildren(pre);
+            const type = preview.mime_type ? ` · ${preview.mime_type}` : '';
+            infoElement.textContent = `二进制文件${type}${preview.truncated ? ' · 仅显示开头部分' : ''}`;
+            break;
+        }
+    }
+
+    overlay.style.display = 'flex';
+    requestAnimationFrame(() => {
+        overlay!.classList.add('visible');
+        isVisible = true;
+    });
+}
@@ -0,0 +1,184 @@
+import type { SearchMatch } from "../services/fileService";
+
+/**
+ * Callbacks connecting the content search dialog to the search service.
+ * 将内容搜索对话框与搜索服务连接起来的回调。
+ */
+export interface SearchDialogCallbacks {
+    onSearch: (pattern: string, regex: boolean, caseSensitive: boolean) => void;
+    onStop: () => void;
+    onResultClick: (match: SearchMatch) => void;
+}
+
+// DOM Element References
+let overlay: HTMLElement | null = null;
+let patternInput: HTMLInputElement | null = null;
+let regexCheckbox: HTMLInputElement | null = null;
+let caseCheckbox: HTMLInputElement | null = null;
+let statusElement: HTMLElement | null = null;
+let resultsElement: HTMLElement | null = null;
+let startButton: HTMLButtonElement | null = null;
+let stopButton: HTMLElement | null = null;
+
+// State
+let callbacks: SearchDialogCallbacks | null = null;
+let isInitialized = false;
+let isVisible = false;
+let isHiding = false;
+let isRunning = false;
+
+function initializeDialog() {
+    if (isInitialized) return;
+
+    overlay = document.getElementById('search-dialog-overlay');
+    patternInput = document.getElementById('search-dialog-pattern') as HTMLInputElement | null;
+    regexCheckbox = document.getElementById('search-dialog-regex') as HTMLInputElement | null;
+    caseCheckbox = document.getElementById('search-dialog-case') as HTMLInputElement | null;
+    statusElement = document.getElementById('search-dialog-status');
+    resultsElement = document.getElementById('search-dialog-results');
+    startButton = document.getElementById('search-dialog-start-btn') as HTMLButtonElement | null;
+    stopButton = document.getElementById('search-dialog-stop-btn');
+    const closeButton = document.getElementById('search-dialog-close-btn');
+
+    if (!overlay || !patternInput || !regexCheckbox || !caseCheckbox || !statusElement || !resultsElement
+        || !startButton || !stopButton || !closeButton) {
+        console.error('Search dialog elements not found in DOM!');
+        return;
+    }
+
+    startButton.addEventListener('click', startSearch);
+    patternInput.addEventListener('keydown', (event) => {
+        if (event.key === 'Enter') {
+            startSearch();
+        }
+    });
+    stopButton.addEventListener('click', () => callbacks?.onStop());
+    closeButton.addEventListener('click', hideDialog);
+    overlay.addEventListener('click', (event) => {
+        // Close only if clicking directly on the overlay, not the dialog content
+        if (event.target === overlay) {
+            hideDialog();
+        }
+    });
+    overlay.addEventListener('transitionend', handleTransitionEnd);
+
+    isInitialized = true;
+}
+
+function startSearch() {
+    if (isRunning || !patternInput || !callbacks) return;
+    const pattern = patternInput.value;
+    if (!pattern) return;
+    callbacks.onSearch(pattern, regexCheckbox?.checked ?? false, caseCheckbox?.checked ?? false);
+}
+
+function hideDialog() {
+    if (!isVisible || isHiding || !overlay) return;
+    // A search nobody can see any more is stopped / 停止已无人查看的搜索
+    if (isRunning) {
+        callbacks?.onStop();
+    }
+    isHiding = true;
+    overlay.classList.remove('visible');
+    // Actual hiding (display: none) is handled in handleTransitionEnd
+}
+
+function handleTransitionEnd(event: TransitionEvent) {
+    if (overlay && event.target === overlay && event.propertyName === 'opacity' && !overlay.classList.contains('visible')) {
+        overlay.style.display = 'none';
+        resultsElement?.replaceChildren();
+        callbacks = null;
+        isHiding = false;
+        isVisible = false;
+    }
+}
+
+/**
+ * Shows the content search dialog.
+ * 显示内容搜索对话框。
+ *
+ * @param initialPattern - Text to prefill, e.g. from the name search box.
+ *                       - 预先填入的文本，例如来自文件名搜索框的内容。
+ * @param dialogCallbacks - Callbacks for starting, stopping and opening results.
+ *                        - 用于开始、停止搜索以及打开结果的回调。
+ */
+export function showSearchDialog(initialPattern: string, dialogCallbacks: SearchDialogCallbacks) {
+    initializeDialog();
+    if (!isInitialized || !overlay || !patternInput || !resultsElement || isHiding) {
+        console.warn("Search dialog cannot be shown.");
+        return;
+    }
+
+    callbacks = dialogCallbacks;
+    patternInput.value = initialPattern;
+    resultsElement.replaceChildren();
+    setSearchStatus("", false);
+
+    overlay.style.display = 'flex';
+    requestAnimationFrame(() => {
+        overlay!.classList.add('visible');
+        isVisible = true;
+        patternInput!.focus();
+    });
+}
+
+/**
+ * Removes the results of the previous search.
+ * 清除上一次搜索的结果。
+ */
+export function clearSearchResults() {
+    resultsElement?.replaceChildren();
+}
+
+/**
+ * Adds a matching line to the result list.
+ * 向结果列表添加一个匹配行。
+ *
+ * @param match - The match emitted by the backend.
+ *              - 后端发出的匹配。
+ */
+export function appendSearchMatch(match: SearchMatch) {
+    if (!resultsElement) return;
+
+    const row = document.createElement('div');
+    row.className = 'search-result';
+    row.title = match.entry_path;
+
+    const location = document.createElement('div');
+    location.className = 'search-result-location';
+    location.textContent = `${match.entry_path}:${match.line_number}:${match.column}`;
+
+    // Context lines are shown around the matching line, which is highlighted
+    // 在匹配行周围显示上下文行，匹配行本身高亮显示
+    const pre = document.createElement('pre');
+    const firstLine = match.line_number - match.context_before.length;
+    const lines = [...match.context_before, match.line, ...match.context_after];
+    lines.forEach((text, index) => {
+        const lineElement = document.createElement('div');
+        lineElement.textContent = `${firstLine + index}: ${text}`;
+        if (index === match.context_before.length) {
+            lineElement.className = 'search-result-line';
+        }
+        pre.appendChild(lineElement);
+    });
+
+    row.append(location, pre);
+    row.addEventListener('click', () => callbacks?.onResultClick(match));
+    resultsElement.appendChild(row);
+}
+
+/**
+ * Updates the status line and switches between the running and idle button states.
+ * 更新状态行，并在运行中和空闲的按钮状态之间切换。
+ *
+ * @param message - The status text.
+ *                - 状态文本。
+ * @param running - Whether a search is running.
+ *                - 是否有搜索正在运行。
+ */
+export function setSearchStatus(message: string, running: boolean) {
+    isRunning = running;
+    if (statusElement) statusElement.textContent = message;
+    if (startButton) startButton.disabled = running;
+    if (stopButton) stopButton.style.display = running ? '' : 'none';
+}
@@ -20,6 +20,8 @@ import {
   showHomePage
 } from "./fileExplorer";
 import { showInfo, showError } from "./notification";
+import { openEntryWithDefaultApp } from "../services/entryService";
+import { releaseArchive, isArchiveEntry, openNestedArchive } from "../services/archiveService";
 import {
   getCurrentArchivePath, 
   getCurrentFiles, 
@@ -54,9 +56,10 @@ export function refreshUI() {
       if (getIsLoading()) return;
       if (file.is_dir) {
         navigateToFolder(file.name); // Use the manager's navigation function
+      } else if (isArchiveEntry(file)) {
+        openNestedArchive(file); // Browse the archive inside the archive
       } else {
-        console.log(`Attempted to preview file (not implemented): ${file.name}`);
-        // Potentially call a file preview service here
+        openEntryWithDefaultApp(file);
       }
     }
   );
@@ -195,6 +198,7 @@ export function performSearch(query: string) {
  * 将应用程序重置到其初始状态（主屏幕）。
  */
 export function resetAppToHome() {
+  releaseArchive(getCurrentArchivePath());
   resetAppState();
   showHomePage();
   setWindowTitle('未打开文件');